@@ -10,17 +10,19 @@ fn main() {
     println!("getMe: {:?}", api.get_me());
     let mut listener = api.listener(ListeningMethod::LongPoll(None));
 
-    // Just to demonstrate this method. Sadly, a server listening for updates
-    // is not (yet!) integrated in this library.
-    println!("Webhook: {:?}", api.set_webhook(Some("https://example.com")));
-    println!("Webhook: {:?}", api.set_webhook::<&str>(None));
+    // Just to demonstrate this method. To receive the updates sent to a
+    // webhook, see `WebhookServer`. This example uses long polling instead.
+    println!("Webhook: {:?}", api.set_webhook(Some("https://example.com"), None, None, None));
+    println!("Webhook: {:?}", api.set_webhook::<&str>(None, None, None, None));
 
     // Fetch new updates via long poll method
     let res = listener.listen(|u| {
-        // If the received update contains a message...
-        if let Some(m) = u.message {
-            let name = m.from.first_name + &*m.from.last_name
-                .map_or("".to_string(), |mut n| { n.insert(0, ' '); n });
+        // If the received update is a new message...
+        if let UpdateKind::Message(m) = u.kind {
+            let name = m.from.map_or("".to_string(), |f| {
+                f.first_name + &*f.last_name
+                    .map_or("".to_string(), |mut n| { n.insert(0, ' '); n })
+            });
             let chat_id = m.chat.id();
 
             // Match message type
@@ -62,7 +64,8 @@ fn main() {
                         loc.longitude + 180.0
                     };
 
-                    try!(api.send_location(chat_id, lat, lng, None, None));
+                    try!(api.send_location(chat_id, lat, lng, None,
+                                           LocationOptions::default(), None, None));
                 },
                 MessageType::Contact(c) => {
                     // Print event
@@ -70,7 +73,7 @@ fn main() {
                         json::encode(&c).unwrap());
 
                     // Just forward the contact back to the sender...
-                    try!(api.forward_message(chat_id, chat_id, m.message_id));
+                    try!(api.forward_message(chat_id, chat_id, m.message_id, None, None));
                 }
                 _ => {}
             }
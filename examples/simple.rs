@@ -10,9 +10,9 @@ fn main() {
 
     // Fetch new updates via long poll method
     let res = listener.listen(|u| {
-        // If the received update contains a message...
-        if let Some(m) = u.message {
-            let name = m.from.first_name;
+        // If the received update is a new message...
+        if let UpdateKind::Message(m) = u.kind {
+            let name = m.from.map_or("".to_string(), |f| f.first_name);
 
             // Match message type
             match m.msg {
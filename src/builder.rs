@@ -0,0 +1,183 @@
+//! Creating a configured `Api` in one go.
+
+use std::fmt;
+use std::time::Duration;
+
+use super::{Api, Error, RateLimit, Result, RetryPolicy};
+use util::Token;
+
+/// Collects the configuration of an `Api`, which is created (and checked)
+/// by `build`. Every method corresponds to one of the setters of `Api`, so
+/// see there for the defaults. For an `Api` with the default configuration,
+/// `Api::from_token` is enough.
+///
+/// ```
+/// use std::time::Duration;
+/// use telegram_bot::*;
+///
+/// let api = ApiBuilder::new()
+///     .token("123:abc")
+///     .timeout(Some(Duration::from_secs(30)))
+///     .connect_timeout(Some(Duration::from_secs(5)))
+///     .retry_policy(RetryPolicy::new(3))
+///     .user_agent("my-bot/1.0")
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Default)]
+pub struct ApiBuilder {
+    token: Option<Token>,
+    debug: bool,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<String>,
+    retry_policy: Option<RetryPolicy>,
+    max_response_size: Option<u64>,
+    user_agent: Option<String>,
+    headers: Vec<(String, String)>,
+    rate_limits: (Option<RateLimit>, Option<RateLimit>),
+}
+
+// Like for `Api`, the token, the proxy URL (which may contain a password)
+// and the header values are hidden.
+impl fmt::Debug for ApiBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ApiBuilder")
+            .field("token", &self.token)
+            .field("debug", &self.debug)
+            .field("read_timeout", &self.read_timeout)
+            .field("write_timeout", &self.write_timeout)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("has_proxy", &self.proxy.is_some())
+            .field("retry_policy", &self.retry_policy)
+            .field("max_response_size", &self.max_response_size)
+            .field("user_agent", &self.user_agent)
+            .field("headers", &self.headers.iter().map(|h| &h.0).collect::<Vec<_>>())
+            .field("rate_limits", &self.rate_limits)
+            .finish()
+    }
+}
+
+impl ApiBuilder {
+    pub fn new() -> ApiBuilder {
+        ApiBuilder::default()
+    }
+
+    /// Sets the bot token, which is required. It's normalized like by
+    /// `Api::from_token`.
+    pub fn token(mut self, token: &str) -> Self {
+        self.token = Some(Token::new(token));
+        self
+    }
+
+    /// See `Api::set_debug`.
+    pub fn debug(mut self, debug: bool) -> Self {
+        self.debug = debug;
+        self
+    }
+
+    /// Sets the read and write timeout, see `Api::set_timeout`.
+    pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.read_timeout = timeout;
+        self.write_timeout = timeout;
+        self
+    }
+
+    /// See `Api::set_read_timeout`.
+    pub fn read_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.read_timeout = timeout;
+        self
+    }
+
+    /// See `Api::set_write_timeout`.
+    pub fn write_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.write_timeout = timeout;
+        self
+    }
+
+    /// See `Api::set_connect_timeout`.
+    pub fn connect_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Sets the URL of the proxy (see `Api::set_proxy`), which is parsed by
+    /// `build`. Only available with the "proxy" feature.
+    #[cfg(feature = "proxy")]
+    pub fn proxy(mut self, proxy_url: &str) -> Self {
+        self.proxy = Some(proxy_url.into());
+        self
+    }
+
+    /// See `Api::set_retry_policy`.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// See `Api::set_max_response_size`.
+    pub fn max_response_size(mut self, max_bytes: u64) -> Self {
+        self.max_response_size = Some(max_bytes);
+        self
+    }
+
+    /// See `Api::set_user_agent`.
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Adds a header sent with every request, see `Api::set_header`.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// See `Api::set_rate_limits`.
+    pub fn rate_limits(mut self, private: Option<RateLimit>, group: Option<RateLimit>) -> Self {
+        self.rate_limits = (private, group);
+        self
+    }
+
+    /// Creates the `Api`. Returns `Error::InvalidArgument` if no token or an
+    /// invalid proxy URL was given and `Error::InvalidTokenFormat` if the
+    /// token is invalid.
+    pub fn build(self) -> Result<Api> {
+        let token = match self.token {
+            Some(token) => token,
+            None => return Err(Error::InvalidArgument("No bot token was given".into())),
+        };
+        let mut api = try!(Api::from_token(token.secret()));
+
+        api.debug = self.debug;
+        api.read_timeout = self.read_timeout;
+        api.write_timeout = self.write_timeout;
+        if let Some(policy) = self.retry_policy {
+            api.retry_policy = policy;
+        }
+        if let Some(max_bytes) = self.max_response_size {
+            api.max_response_size = max_bytes;
+        }
+        if let Some(user_agent) = self.user_agent {
+            api.user_agent = user_agent;
+        }
+        for (name, value) in self.headers {
+            api.set_header(&name, &value);
+        }
+        api.rate_limits = self.rate_limits;
+
+        // Only create a new connection pool if its settings changed
+        #[cfg(feature = "proxy")]
+        {
+            if let Some(ref url) = self.proxy {
+                api.proxy = Some(try!(::proxy::Proxy::parse(url)));
+            }
+        }
+        if self.connect_timeout.is_some() || self.proxy.is_some() {
+            api.connect_timeout = self.connect_timeout;
+            api.renew_connector();
+        }
+        Ok(api)
+    }
+}
@@ -0,0 +1,85 @@
+//! The connector used for all connections to the Telegram servers.
+
+use std::io;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+use hyper::net::{HttpStream, HttpsStream, NetworkConnector, Ssl};
+
+#[cfg(feature = "proxy")]
+use proxy::Proxy;
+
+/// Connector which works like the default connector of hyper, but supports
+/// a connect timeout and (with the "proxy" feature) tunneling all
+/// connections through a proxy.
+#[derive(Debug, Default)]
+pub struct Connector<S: Ssl> {
+    connect_timeout: Option<Duration>,
+    #[cfg(feature = "proxy")]
+    proxy: Option<Proxy>,
+    ssl: S,
+}
+
+impl<S: Ssl + Default> Connector<S> {
+    pub fn new(connect_timeout: Option<Duration>) -> Connector<S> {
+        Connector {
+            connect_timeout: connect_timeout,
+            ..Connector::default()
+        }
+    }
+}
+
+impl<S: Ssl> Connector<S> {
+    #[cfg(feature = "proxy")]
+    pub fn with_proxy(mut self, proxy: Option<Proxy>) -> Connector<S> {
+        self.proxy = proxy;
+        self
+    }
+
+    #[cfg(feature = "proxy")]
+    fn connect_tcp(&self, host: &str, port: u16) -> io::Result<TcpStream> {
+        match self.proxy {
+            Some(ref proxy) => proxy.tunnel(host, port, self.connect_timeout),
+            None => connect_tcp(host, port, self.connect_timeout),
+        }
+    }
+
+    #[cfg(not(feature = "proxy"))]
+    fn connect_tcp(&self, host: &str, port: u16) -> io::Result<TcpStream> {
+        connect_tcp(host, port, self.connect_timeout)
+    }
+}
+
+impl<S: Ssl> NetworkConnector for Connector<S> {
+    type Stream = HttpsStream<S::Stream>;
+
+    fn connect(&self, host: &str, port: u16, scheme: &str) -> ::hyper::Result<Self::Stream> {
+        let stream = HttpStream(try!(self.connect_tcp(host, port)));
+
+        if scheme == "https" {
+            self.ssl.wrap_client(stream, host).map(HttpsStream::Https)
+        } else {
+            Ok(HttpsStream::Http(stream))
+        }
+    }
+}
+
+// Connects to the first address of the host that accepts the connection
+// within the timeout. Without a timeout, the operating system decides how
+// long to wait.
+pub fn connect_tcp(host: &str, port: u16, timeout: Option<Duration>) -> io::Result<TcpStream> {
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return TcpStream::connect((host, port)),
+    };
+
+    let mut last_err = None;
+    for addr in try!((host, port).to_socket_addrs()) {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_err = Some(e),
+        }
+    }
+    Err(last_err.unwrap_or_else(|| {
+        io::Error::new(io::ErrorKind::Other, format!("{} has no addresses", host))
+    }))
+}
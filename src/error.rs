@@ -2,11 +2,28 @@ use std::fmt;
 use rustc_serialize::json;
 use std::env;
 
+use types::Integer;
+
 /// Telegram-Bot Result
 pub type Result<T> = ::std::result::Result<T, Error>;
 
 /// Telegram-Bot Error: Anything that may fail (HTTP, JSON, ...)
+///
+/// New variants may be added in minor releases, so a `match` on an error
+/// has to contain a `_` arm:
+///
+/// ```
+/// # use telegram_bot::Error;
+/// fn should_retry(e: &Error) -> bool {
+///     match *e {
+///         Error::RateLimited { .. } | Error::Io(_) => true,
+///         _ => false,
+///     }
+/// }
+/// # assert!(!should_retry(&Error::Api("Bad Request".into())));
+/// ```
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// HTTP related error
     Http(::hyper::error::Error),
@@ -16,8 +33,18 @@ pub enum Error {
     JsonDecode(json::DecoderError),
     /// Error while encoding JSON data
     JsonEncode(json::EncoderError),
+    /// The response of the API method `method` could not be decoded, e.g.
+    /// because it has an unexpected shape.
+    ResponseDecode { method: String, error: json::DecoderError },
     /// Telegram server reponsded with an error + description
     Api(String),
+    /// The group was migrated to a supergroup with the id `to`. Requests
+    /// have to use the new id.
+    ChatMigrated { to: Integer },
+    /// Too many requests were sent, the request may be repeated after
+    /// `retry_after` seconds. The delay is taken from the response of the
+    /// API or, if that doesn't contain it, from the "Retry-After" header.
+    RateLimited { retry_after: Integer },
     /// This should never happen (it possibly could if the telegram servers
     /// would respond with garbage)
     InvalidState(String),
@@ -27,6 +54,56 @@ pub enum Error {
     InvalidEnvironmentVar(env::VarError),
     /// The given path is not valid.
     InvalidPath(String),
+    /// An argument is outside of the range the API accepts.
+    InvalidArgument(String),
+    /// A downloaded file or a response is bigger than the limit of `limit`
+    /// bytes.
+    TooLarge { limit: u64 },
+    /// The update handler panicked with the given message (see
+    /// `Listener::set_catch_panics`).
+    HandlerPanicked(String),
+    /// The server didn't respond with JSON, e.g. because a gateway in front
+    /// of the API returned an HTML error page. Contains the status, the
+    /// content type and the beginning of the body.
+    UnexpectedResponse {
+        status: ::hyper::status::StatusCode,
+        content_type: String,
+        body: String,
+    },
+}
+
+impl Error {
+    /// Returns true if repeating the request that failed with this error may
+    /// succeed: Network errors (including timeouts), server errors of
+    /// gateways and rate limiting. Errors like invalid parameters or non
+    /// existing chats are not retryable.
+    pub fn is_retryable(&self) -> bool {
+        match *self {
+            Error::Io(_) | Error::Http(::hyper::Error::Io(_)) => true,
+            Error::UnexpectedResponse { status, .. } => status.is_server_error(),
+            _ => self.is_rate_limited(),
+        }
+    }
+
+    /// Returns true if the Telegram servers refused the request because too
+    /// many requests were sent.
+    pub fn is_rate_limited(&self) -> bool {
+        match *self {
+            Error::RateLimited { .. } => true,
+            Error::Api(ref s) => s.starts_with("Too Many Requests"),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the Telegram servers refused the request because the
+    /// bot isn't allowed to do it, e.g. because the user blocked the bot or
+    /// the bot lacks a permission (like the one to verify users).
+    pub fn is_forbidden(&self) -> bool {
+        match *self {
+            Error::Api(ref s) => s.starts_with("Forbidden"),
+            _ => false,
+        }
+    }
 }
 
 impl ::std::error::Error for Error {
@@ -36,11 +113,18 @@ impl ::std::error::Error for Error {
             Error::Io(ref e) => e.description(),
             Error::JsonDecode(ref e) => e.description(),
             Error::JsonEncode(ref e) => e.description(),
+            Error::ResponseDecode { ref error, .. } => error.description(),
             Error::Api(ref s) => &s,
+            Error::ChatMigrated { .. } => "Group chat was migrated to a supergroup",
+            Error::RateLimited { .. } => "Too many requests",
             Error::InvalidState(ref s) => &s,
             Error::InvalidTokenFormat(ref e) => e.description(),
             Error::InvalidEnvironmentVar(ref e) => e.description(),
             Error::InvalidPath(ref s) => &s,
+            Error::InvalidArgument(ref s) => &s,
+            Error::TooLarge { .. } => "Size limit exceeded",
+            Error::HandlerPanicked(ref s) => &s,
+            Error::UnexpectedResponse { .. } => "Server did not respond with JSON",
         }
     }
 }
@@ -52,11 +136,27 @@ impl fmt::Display for Error {
             Error::Io(ref e) => e.fmt(f),
             Error::JsonDecode(ref e) => e.fmt(f),
             Error::JsonEncode(ref e) => e.fmt(f),
+            Error::ResponseDecode { ref method, ref error } => {
+                write!(f, "while decoding {} response: {}", method, error)
+            }
             Error::Api(ref s) => s.fmt(f),
+            Error::ChatMigrated { to } => {
+                write!(f, "Group chat was migrated to the supergroup {}", to)
+            }
+            Error::RateLimited { retry_after } => {
+                write!(f, "Too many requests, retry after {} seconds", retry_after)
+            }
             Error::InvalidState(ref s) => s.fmt(f),
             Error::InvalidTokenFormat(ref e) => e.fmt(f),
             Error::InvalidEnvironmentVar(ref e) => e.fmt(f),
             Error::InvalidPath(ref s) => s.fmt(f),
+            Error::InvalidArgument(ref s) => s.fmt(f),
+            Error::TooLarge { limit } => write!(f, "Size limit of {} bytes exceeded", limit),
+            Error::HandlerPanicked(ref s) => write!(f, "Update handler panicked: {}", s),
+            Error::UnexpectedResponse { status, ref content_type, ref body } => {
+                write!(f, "Server returned {} with status {} instead of JSON: {}",
+                       content_type, status, body)
+            }
         }
     }
 }
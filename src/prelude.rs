@@ -0,0 +1,26 @@
+//! Re-exports of the types most bots need, to keep `use` lists short:
+//!
+//! ```no_run
+//! use telegram_bot::prelude::*;
+//!
+//! let api = Api::from_env("TELEGRAM_BOT_TOKEN").unwrap();
+//! let mut listener = api.listener(ListeningMethod::LongPoll(None));
+//! listener.listen(|u| {
+//!     if let UpdateKind::Message(m) = u.kind {
+//!         try!(api.send_message(m.chat_id(), "Hi!".into(),
+//!                               Some(ParseMode::Markdown), None, None, None));
+//!     }
+//!     Ok(ListeningAction::Continue)
+//! }).unwrap();
+//! ```
+//!
+//! The crate's `Result` type is not included, so it doesn't shadow the one
+//! of the standard library.
+
+pub use {Api, Listener, ListeningAction, ListeningMethod, WebhookServer};
+pub use Error;
+pub use types::{Chat, ChatAction, ChatId, FileSource, Integer, Message, MessageId,
+                MessageType, ParseMode, SendMessageOptions, Update, UpdateKind, User,
+                UserId};
+pub use types::{ForceReply, InlineKeyboardButton, InlineKeyboardMarkup,
+                ReplyKeyboardHide, ReplyKeyboardMarkup, ReplyMarkup};
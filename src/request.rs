@@ -0,0 +1,142 @@
+//! Requests that are built first and executed later.
+
+use rustc_serialize::{json, Decodable, Encodable};
+use std::time::Duration;
+
+use super::{method_url, Api, FileSource, RequestType, Result};
+use util::Params;
+
+/// A request to an API method, created via `Api::request`. The request can
+/// be inspected (e.g. in tests) before it's sent via `execute`. The typed
+/// methods like `Api::send_message` build their requests like this, too, and
+/// pass them to the hook set via `Api::set_request_hook`.
+///
+/// ```
+/// use telegram_bot::*;
+///
+/// let api = Api::from_token("123:abc").unwrap();
+/// let request = api.request("sendMessage")
+///     .param("chat_id", 123456789)
+///     .param("text", "Hi!");
+/// assert_eq!(request.url(), "https://api.telegram.org/bot<token>/sendMessage");
+/// assert_eq!(request.params()[1], ("text".to_string(), "Hi!".to_string()));
+/// ```
+pub struct RequestBuilder<'a> {
+    api: &'a Api,
+    method: String,
+    params: Vec<(String, String)>,
+    files: Vec<(String, FileSource)>,
+    timeout: Option<Duration>,
+    max_retries: Option<u32>,
+}
+
+impl<'a> RequestBuilder<'a> {
+    /// Creates a request without parameters to the API method `method`.
+    /// Same as `api.request(method)`.
+    pub fn new(api: &'a Api, method: &str) -> RequestBuilder<'a> {
+        RequestBuilder {
+            api: api,
+            method: method.into(),
+            params: Vec::new(),
+            files: Vec::new(),
+            timeout: api.read_timeout,
+            max_retries: None,
+        }
+    }
+
+    /// Adds a parameter.
+    pub fn param<V: ToString>(mut self, key: &str, value: V) -> Self {
+        self.params.push((key.into(), value.to_string()));
+        self
+    }
+
+    /// Replaces the value of the parameter, or adds it if it doesn't exist.
+    /// Useful in the request hook (see `Api::set_request_hook`).
+    pub fn set_param<V: ToString>(&mut self, key: &str, value: V) {
+        match self.params.iter_mut().find(|&&mut (ref k, _)| k == key) {
+            Some(&mut (_, ref mut v)) => *v = value.to_string(),
+            None => self.params.push((key.into(), value.to_string())),
+        }
+    }
+
+    /// Adds a parameter that is encoded as JSON, like all array and object
+    /// parameters.
+    pub fn json_param<V: Encodable>(mut self, key: &str, value: V) -> Result<Self> {
+        self.params.push((key.into(), try!(json::encode(&value))));
+        Ok(self)
+    }
+
+    /// Adds a file. Files given by id or URL are added as normal parameters,
+    /// all others are uploaded.
+    pub fn file<F: Into<FileSource>>(mut self, key: &str, file: F) -> Self {
+        match file.into() {
+            FileSource::FileId(s) | FileSource::Url(s) => {
+                self.params.push((key.into(), s))
+            }
+            file => self.files.push((key.into(), file)),
+        }
+        self
+    }
+
+    /// Returns the name of the API method.
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    /// Returns the parameters in the order they were added. Files that are
+    /// uploaded are not included.
+    pub fn params(&self) -> &[(String, String)] {
+        &self.params
+    }
+
+    /// Returns the files that are uploaded.
+    pub fn files(&self) -> &[(String, FileSource)] {
+        &self.files
+    }
+
+    /// Returns the URL the request is sent to, with the bot token replaced
+    /// by `<token>`.
+    pub fn url(&self) -> String {
+        method_url("<token>", &self.method)
+    }
+
+    /// Passes the request to the request hook, if there is one, and sends it
+    /// and decodes the result.
+    pub fn execute<T: Decodable>(mut self) -> Result<T> {
+        let api = self.api;
+        if let Some(ref hook) = api.request_hook {
+            try!(hook(&mut self));
+        }
+        let RequestBuilder { api, method, params, files, timeout, max_retries } = self;
+
+        let mut p = Params::new();
+        for &(ref k, ref v) in &params {
+            p.add_get(k, v.clone());
+        }
+
+        let typ = if files.is_empty() {
+            RequestType::Post
+        } else {
+            RequestType::Multipart(files)
+        };
+        api.execute_request(&method, p, typ, timeout, max_retries)
+    }
+}
+
+// Builds the request of a typed method from its parameters.
+pub fn from_params<'a>(api: &'a Api, method: &str, p: &Params, typ: RequestType,
+                       timeout: Option<Duration>, max_retries: Option<u32>)
+                       -> RequestBuilder<'a> {
+    let files = match typ {
+        RequestType::Post => Vec::new(),
+        RequestType::Multipart(files) => files,
+    };
+    RequestBuilder {
+        api: api,
+        method: method.into(),
+        params: p.get_params().iter().map(|&(k, ref v)| (k.into(), v.clone())).collect(),
+        files: files,
+        timeout: timeout,
+        max_retries: max_retries,
+    }
+}
@@ -0,0 +1,222 @@
+//! Shortening formatted texts to the length limits of the API.
+
+use types::ParseMode;
+
+// Appended to truncated texts
+const ELLIPSIS: char = '\u{2026}';
+
+// Part of a formatted text
+enum Token<'a> {
+    // Text that must not be split, e.g. a character, an escape sequence or
+    // a whole link
+    Text(&'a str),
+    // Markup starting an entity, together with the markup ending it
+    Open(&'a str, String),
+    // Markup ending the innermost entity
+    Close(&'a str),
+}
+
+/// Shortens `text` to at most `limit` characters and marks the cut with an
+/// ellipsis ("…"). Texts that are short enough are returned unchanged. The
+/// API allows 4096 characters for message texts and 1024 for captions.
+///
+/// With a `parse_mode`, the markup counts towards the limit and the text is
+/// never cut within an escape sequence, a HTML tag or entity or a link, and
+/// entities that are open at the cut are closed after the ellipsis. So the
+/// result is still valid markup, if `text` was.
+///
+/// ```
+/// use telegram_bot::*;
+///
+/// assert_eq!(truncate_message("Hello world", 8, None), "Hello w…");
+/// assert_eq!(truncate_message("<b>Hello</b> world", 12, Some(ParseMode::Html)),
+///            "<b>Hell…</b>");
+/// assert_eq!(truncate_message(r"*1\+1 is 2*", 8, Some(ParseMode::MarkdownV2)),
+///            r"*1\+1 …*");
+/// ```
+pub fn truncate_message(text: &str, limit: usize, parse_mode: Option<ParseMode>) -> String {
+    if text.chars().count() <= limit {
+        return text.into();
+    }
+    if limit == 0 {
+        return String::new();
+    }
+
+    let tokens = match parse_mode {
+        None => plain_tokens(text),
+        Some(ParseMode::Html) => html_tokens(text),
+        Some(ParseMode::Markdown) => markdown_tokens(text, false),
+        Some(ParseMode::MarkdownV2) => markdown_tokens(text, true),
+    };
+
+    // Take tokens as long as they fit together with the ellipsis and the
+    // markup closing all open entities
+    let mut out = String::new();
+    let mut len = 0;
+    let mut closing: Vec<String> = Vec::new();
+    let mut closing_len = 0;
+    for token in tokens {
+        match token {
+            Token::Open(s, close) => {
+                let close_len = close.chars().count();
+                let s_len = s.chars().count();
+                if len + s_len + closing_len + close_len + 1 > limit {
+                    break;
+                }
+                out.push_str(s);
+                len += s_len;
+                closing_len += close_len;
+                closing.push(close);
+            }
+            // Closing an entity never needs more space than reserved
+            Token::Close(s) if !closing.is_empty() => {
+                if let Some(close) = closing.pop() {
+                    closing_len -= close.chars().count();
+                }
+                out.push_str(s);
+                len += s.chars().count();
+            }
+            Token::Text(s) | Token::Close(s) => {
+                let s_len = s.chars().count();
+                if len + s_len + closing_len + 1 > limit {
+                    break;
+                }
+                out.push_str(s);
+                len += s_len;
+            }
+        }
+    }
+
+    out.push(ELLIPSIS);
+    for close in closing.iter().rev() {
+        out.push_str(close);
+    }
+    out
+}
+
+fn plain_tokens<'a>(text: &'a str) -> Vec<Token<'a>> {
+    text.char_indices()
+        .map(|(i, c)| Token::Text(&text[i..i + c.len_utf8()]))
+        .collect()
+}
+
+// Returns the first character of `s` (which must not be empty).
+fn first_char(s: &str) -> &str {
+    let len = s.chars().next().map_or(0, |c| c.len_utf8());
+    &s[..len]
+}
+
+fn html_tokens<'a>(text: &'a str) -> Vec<Token<'a>> {
+    let mut tokens = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let token_len = if rest.starts_with('<') {
+            rest.find('>').map(|end| end + 1)
+        } else if rest.starts_with('&') {
+            // Entities like "&amp;" or "&#8212;"
+            rest.find(';').filter(|&end| end <= 10).map(|end| end + 1)
+        } else {
+            None
+        };
+
+        let token = match token_len {
+            Some(len) if rest.starts_with("</") => Token::Close(&rest[..len]),
+            Some(len) if rest.starts_with('<') && !rest[..len].ends_with("/>") => {
+                let tag = &rest[..len];
+                let name = tag[1..len - 1].split_whitespace().next().unwrap_or("");
+                Token::Open(tag, format!("</{}>", name))
+            }
+            Some(len) => Token::Text(&rest[..len]),
+            None => Token::Text(first_char(rest)),
+        };
+        rest = &rest[token_str(&token).len()..];
+        tokens.push(token);
+    }
+    tokens
+}
+
+fn markdown_tokens<'a>(text: &'a str, v2: bool) -> Vec<Token<'a>> {
+    let markers: &[&str] = if v2 {
+        &["```", "`", "__", "||", "*", "_", "~"]
+    } else {
+        &["```", "`", "*", "_"]
+    };
+
+    let mut tokens = Vec::new();
+    let mut open: Vec<&str> = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let in_code = open.last().map_or(false, |m| m.starts_with('`'));
+        let marker = markers.iter().cloned().find(|m| rest.starts_with(m));
+
+        let token = if rest.starts_with('\\') && rest.len() > 1 {
+            let len = 1 + first_char(&rest[1..]).len();
+            Token::Text(&rest[..len])
+        } else if in_code {
+            // Only the marker ending the code is special within it
+            match open.last() {
+                Some(&m) if rest.starts_with(m) => {
+                    open.pop();
+                    Token::Close(&rest[..m.len()])
+                }
+                _ => Token::Text(first_char(rest)),
+            }
+        } else if let Some(len) = link_len(rest, v2) {
+            Token::Text(&rest[..len])
+        } else if let Some(m) = marker {
+            if open.last() == Some(&m) {
+                open.pop();
+                Token::Close(&rest[..m.len()])
+            } else {
+                open.push(m);
+                Token::Open(&rest[..m.len()], m.into())
+            }
+        } else {
+            Token::Text(first_char(rest))
+        };
+        rest = &rest[token_str(&token).len()..];
+        tokens.push(token);
+    }
+    tokens
+}
+
+// Returns the length of the link (like "[text](url)") `s` starts with. With
+// MarkdownV2, custom emoji ("![👍](tg://emoji?id=...)") are links, too.
+fn link_len(s: &str, v2: bool) -> Option<usize> {
+    let start = if s.starts_with('[') {
+        1
+    } else if v2 && s.starts_with("![") {
+        2
+    } else {
+        return None;
+    };
+
+    let text_end = match find_unescaped(&s[start..], ']') {
+        Some(end) if s[start + end..].starts_with("](") => start + end,
+        _ => return None,
+    };
+    let url_start = text_end + 2;
+    find_unescaped(&s[url_start..], ')').map(|end| url_start + end + 1)
+}
+
+// Returns the position of the first `c` in `s` that isn't escaped by a
+// backslash.
+fn find_unescaped(s: &str, c: char) -> Option<usize> {
+    let mut escaped = false;
+    for (i, ch) in s.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if ch == '\\' {
+            escaped = true;
+        } else if ch == c {
+            return Some(i);
+        }
+    }
+    None
+}
+
+fn token_str<'a>(token: &Token<'a>) -> &'a str {
+    match *token {
+        Token::Text(s) | Token::Open(s, _) | Token::Close(s) => s,
+    }
+}
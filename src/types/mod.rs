@@ -7,45 +7,81 @@
 //!
 //! All types implement `Decodable` and `Encodable`, so they can be serialized
 //! as JSON. Non existing JSON-fields will result in `None` values for `Option`
-//! types. `None` values don't result in JSON fields.
+//! types. `None` values don't result in JSON fields. JSON-fields that are
+//! not part of a type (e.g. because they were added in a newer version of
+//! the API) are ignored, and unknown kinds of messages are decoded as
+//! `MessageType::Unknown`.
 //!
 
 use rustc_serialize::{Decodable, Encodable, Decoder, Encoder};
+use rustc_serialize::json::{self, Json};
 use std::convert::Into;
 use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 // ===========================================================================
 // Helpers
 // ===========================================================================
 // Macro to implement "Encodable" quickly. "None" fields won't be encoded.
+// With `type = "..."`, a "type" field with the given value is encoded first
+// (used for the different kinds of e.g. inline query results).
 macro_rules! impl_encode {
     (
         $ty:ident, $count:expr,
         [$($id:expr => $field:ident),*],
         [$($o_id:expr => $o_field:ident),*]
+    ) => {
+        impl_encode!(@impl $ty, $count, None,
+                     [$($id => $field),*], [$($o_id => $o_field),*]);
+    };
+    (
+        $ty:ident, $count:expr, type = $typ:expr,
+        [$($id:expr => $field:ident),*],
+        [$($o_id:expr => $o_field:ident),*]
+    ) => {
+        impl_encode!(@impl $ty, $count, Some($typ),
+                     [$($id => $field),*], [$($o_id => $o_field),*]);
+    };
+    (
+        @impl $ty:ident, $count:expr, $typ:expr,
+        [$($id:expr => $field:ident),*],
+        [$($o_id:expr => $o_field:ident),*]
     ) => {
         impl Encodable for $ty {
             fn encode<E: Encoder>(&self, e: &mut E) -> Result<(), E::Error> {
                 e.emit_struct(stringify!($ty), $count, |e| {
+                    // The encoder puts a separator before every field with an
+                    // index other than 0, so skipped fields must not leave
+                    // gaps. The given indices only document the order.
+                    let mut idx = 0;
+                    let typ: Option<&'static str> = $typ;
+                    if let Some(typ) = typ {
+                        try!(e.emit_struct_field("type", idx, |e| typ.encode(e)));
+                        idx += 1;
+                    }
                     $(
-                        try!(e.emit_struct_field(stringify!($field), $id, |e| {
+                        try!(e.emit_struct_field(stringify!($field), idx, |e| {
                             self.$field.encode(e)
                         }));
+                        idx += 1;
                     )*
                     $(
                         if let Some(ref v) = self.$o_field {
                             try!(e.emit_struct_field(
-                                stringify!($o_field), $o_id, |e| {
+                                stringify!($o_field), idx, |e| {
                                 v.encode(e)
                             }));
+                            idx += 1;
                         }
                     )*
+                    let _ = idx;
 
                     Ok(())
                 })
             }
         }
-    }
+    };
 }
 
 // Decodes a field with a given name. If successful: Return decoded
@@ -65,6 +101,77 @@ pub type Integer = i64;
 /// The Telegram "Float": Currently f32.
 pub type Float = f32;
 
+// Defines a newtype for an id, which documents what kind of id a parameter
+// expects. The id is converted from and into an `Integer`, sent as parameter
+// like an `Integer` and decoded and encoded like one. The id fields of the
+// types use the id types too, so e.g. a message id can't be given where a
+// chat id is expected.
+macro_rules! id_type {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+        pub struct $name(pub Integer);
+
+        impl From<Integer> for $name {
+            fn from(id: Integer) -> $name {
+                $name(id)
+            }
+        }
+
+        impl From<i32> for $name {
+            fn from(id: i32) -> $name {
+                $name(id as Integer)
+            }
+        }
+
+        impl From<$name> for Integer {
+            fn from(id: $name) -> Integer {
+                id.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl Decodable for $name {
+            fn decode<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
+                Integer::decode(d).map($name)
+            }
+        }
+
+        impl Encodable for $name {
+            fn encode<E: Encoder>(&self, e: &mut E) -> Result<(), E::Error> {
+                self.0.encode(e)
+            }
+        }
+    }
+}
+
+id_type!(
+    /// Id of a chat. API methods accept it as well as a plain `Integer`, but
+    /// not other kinds of ids:
+    ///
+    /// ```compile_fail
+    /// # use telegram_bot::*;
+    /// # fn reply(api: &Api, m: &Message) -> Result<Message> {
+    /// api.send_message(m.message_id, "Hi".into(), None, None, None, None)
+    /// # }
+    /// ```
+    ChatId
+);
+id_type!(
+    /// Id of a user. API methods accept it as well as a plain `Integer`.
+    UserId
+);
+id_type!(
+    /// Id of a message within its chat. API methods accept it as well as a
+    /// plain `Integer`.
+    MessageId
+);
+
 
 // ===========================================================================
 // Types not explicitly mentioned or somehow different from Telegram types
@@ -75,19 +182,30 @@ pub struct Response<T: Decodable> {
     pub ok: bool,
     pub error_code: Option<Integer>,
     pub description: Option<String>,
+    pub parameters: Option<ResponseParameters>,
     pub result: Option<T>,
 }
 
+/// Telegram type "ResponseParameters" (directly mapped). Contains details
+/// of some errors, e.g. after how many seconds the request can be repeated.
+#[derive(RustcDecodable, Debug, PartialEq, Clone, Copy)]
+pub struct ResponseParameters {
+    /// The new id of a group that was migrated to a supergroup
+    pub migrate_to_chat_id: Option<Integer>,
+    /// Number of seconds to wait before the request can be repeated
+    pub retry_after: Option<Integer>,
+}
+
 // ---------------------------------------------------------------------------
-/// Represents one of "ReplyKeyboardMarkup", "ReplyKeyboardHide" or
-/// "ForceReply". Used for the "reply_markup" field.
+/// Represents one of "ReplyKeyboardMarkup", "InlineKeyboardMarkup",
+/// "ReplyKeyboardHide" or "ForceReply". Used for the "reply_markup" field.
 #[derive(Debug, PartialEq, Clone)]
 pub enum ReplyMarkup {
     Keyboard(ReplyKeyboardMarkup),
+    InlineKeyboard(InlineKeyboardMarkup),
     /// The boolean corresponds to the "selective" field of "ReplyKeyboardHide"
     KeyboardHide(bool),
-    /// The boolean corresponds to the "selective" field of "ForceReply"
-    ForceReply(bool),
+    ForceReply(ForceReply),
 }
 
 impl From<ReplyKeyboardMarkup> for ReplyMarkup {
@@ -96,10 +214,44 @@ impl From<ReplyKeyboardMarkup> for ReplyMarkup {
     }
 }
 
+impl From<InlineKeyboardMarkup> for ReplyMarkup {
+    fn from(keyboard: InlineKeyboardMarkup) -> ReplyMarkup {
+        ReplyMarkup::InlineKeyboard(keyboard)
+    }
+}
+
+impl From<ReplyKeyboardHide> for ReplyMarkup {
+    fn from(hide: ReplyKeyboardHide) -> ReplyMarkup {
+        ReplyMarkup::KeyboardHide(hide.selective)
+    }
+}
+
+impl From<ForceReply> for ReplyMarkup {
+    fn from(force: ForceReply) -> ReplyMarkup {
+        ReplyMarkup::ForceReply(force)
+    }
+}
+
+/// Telegram type "ReplyKeyboardHide". Only exists to be converted into a
+/// `ReplyMarkup`.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct ReplyKeyboardHide {
+    pub selective: bool,
+}
+
+/// Telegram type "ForceReply". Converted into a `ReplyMarkup` to be sent.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ForceReply {
+    pub selective: bool,
+    /// Shown in the input field while it's empty, at most 64 characters
+    pub input_field_placeholder: Option<String>,
+}
+
 impl Encodable for ReplyMarkup {
     fn encode<E: Encoder>(&self, e: &mut E) -> Result<(), E::Error> {
         match *self {
             ReplyMarkup::Keyboard(ref k) => k.encode(e),
+            ReplyMarkup::InlineKeyboard(ref k) => k.encode(e),
             ReplyMarkup::KeyboardHide(b) => {
                 e.emit_struct("ReplyKeyboardHide", 2, |e| {
                     try!(e.emit_struct_field("hide_keyboard", 0, |e| {
@@ -108,18 +260,561 @@ impl Encodable for ReplyMarkup {
                     e.emit_struct_field("selective", 1, |e| b.encode(e))
                 })
             },
-            ReplyMarkup::ForceReply(b) => {
-                e.emit_struct("ForceReply", 2, |e| {
+            ReplyMarkup::ForceReply(ref f) => {
+                e.emit_struct("ForceReply", 3, |e| {
                     try!(e.emit_struct_field("force_reply", 0, |e| {
                         true.encode(e)
                     }));
-                    e.emit_struct_field("selective", 1, |e| b.encode(e))
+                    try!(e.emit_struct_field("selective", 1, |e| f.selective.encode(e)));
+                    if let Some(ref placeholder) = f.input_field_placeholder {
+                        try!(e.emit_struct_field("input_field_placeholder", 2, |e| {
+                            placeholder.encode(e)
+                        }));
+                    }
+                    Ok(())
                 })
             },
         }
     }
 }
 
+// ---------------------------------------------------------------------------
+/// Telegram type "LinkPreviewOptions" (directly mapped). Controls how the
+/// preview of a link in a message is shown. Fields that are `None` are not
+/// sent to Telegram.
+#[derive(RustcDecodable, Debug, PartialEq, Clone, Default)]
+pub struct LinkPreviewOptions {
+    pub is_disabled: Option<bool>,
+    /// The URL to show the preview for, instead of the first one in the text
+    pub url: Option<String>,
+    pub prefer_small_media: Option<bool>,
+    pub prefer_large_media: Option<bool>,
+    /// Show the preview above the text instead of below it
+    pub show_above_text: Option<bool>,
+}
+
+impl_encode!(LinkPreviewOptions, 5,
+    [],
+    [0 => is_disabled, 1 => url, 2 => prefer_small_media,
+     3 => prefer_large_media, 4 => show_above_text]);
+
+// ---------------------------------------------------------------------------
+/// Telegram type "ReplyParameters" (directly mapped). Describes the message
+/// a message replies to. Unlike `reply_to_message_id`, the message can be in
+/// another chat and a part of it can be quoted.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ReplyParameters {
+    pub message_id: MessageId,
+    /// The chat of the message, if it's not the chat the reply is sent to
+    pub chat_id: Option<ChatId>,
+    pub allow_sending_without_reply: Option<bool>,
+    /// The quoted part of the message. It has to be an exact substring of
+    /// the message's text.
+    pub quote: Option<String>,
+    pub quote_parse_mode: Option<ParseMode>,
+    /// Position of the quote in UTF-16 code units
+    pub quote_position: Option<Integer>,
+}
+
+impl ReplyParameters {
+    pub fn new<M: Into<MessageId>>(message_id: M) -> ReplyParameters {
+        ReplyParameters {
+            message_id: message_id.into(),
+            chat_id: None,
+            allow_sending_without_reply: None,
+            quote: None,
+            quote_parse_mode: None,
+            quote_position: None,
+        }
+    }
+}
+
+impl_encode!(ReplyParameters, 6,
+    [0 => message_id],
+    [1 => chat_id, 2 => allow_sending_without_reply, 3 => quote,
+     4 => quote_parse_mode, 5 => quote_position]);
+
+// ---------------------------------------------------------------------------
+/// Optional parameters of the `send_message_with_options` method. All fields
+/// default to `None`. The setter methods can be chained:
+///
+/// ```
+/// use telegram_bot::*;
+///
+/// let options = SendMessageOptions::default()
+///     .parse_mode(ParseMode::Markdown)
+///     .reply_markup(ForceReply {
+///         selective: true,
+///         input_field_placeholder: Some("Enter your email".into()),
+///     });
+/// ```
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct SendMessageOptions {
+    pub parse_mode: Option<ParseMode>,
+    /// Shorthand for `LinkPreviewOptions { is_disabled: .. }`. Ignored if
+    /// `link_preview_options` is set.
+    pub disable_web_page_preview: Option<bool>,
+    pub link_preview_options: Option<LinkPreviewOptions>,
+    /// Shorthand for `ReplyParameters::new(..)`, together with
+    /// `allow_sending_without_reply`. Ignored if `reply_parameters` is set.
+    pub reply_to_message_id: Option<MessageId>,
+    pub reply_parameters: Option<ReplyParameters>,
+    pub reply_markup: Option<ReplyMarkup>,
+    /// The forum topic to send the message to (supergroups with topics only)
+    pub message_thread_id: Option<Integer>,
+    /// Sends the message on behalf of the business account of this
+    /// connection (see `UpdateKind::BusinessConnection`)
+    pub business_connection_id: Option<String>,
+    /// Animated effect shown with the message (private chats only)
+    pub message_effect_id: Option<String>,
+    /// Send the message even if the message given by `reply_to_message_id`
+    /// doesn't exist (anymore). It's sent as a normal message then. Setting
+    /// it without `reply_to_message_id` or together with `reply_parameters`
+    /// gives an `Error::InvalidArgument`.
+    pub allow_sending_without_reply: Option<bool>,
+    /// How often the message is sent again after network errors, instead of
+    /// what the `RetryPolicy` of the `Api` allows (its delays are still
+    /// used). `Some(0)` disables retries for this message. Not sent to the
+    /// API.
+    pub max_retries: Option<u32>,
+}
+
+impl SendMessageOptions {
+    pub fn parse_mode(mut self, parse_mode: ParseMode) -> Self {
+        self.parse_mode = Some(parse_mode);
+        self
+    }
+
+    pub fn disable_web_page_preview(mut self, disable: bool) -> Self {
+        self.disable_web_page_preview = Some(disable);
+        self
+    }
+
+    pub fn link_preview_options(mut self, options: LinkPreviewOptions) -> Self {
+        self.link_preview_options = Some(options);
+        self
+    }
+
+    pub fn reply_to_message_id<M: Into<MessageId>>(mut self, message_id: M) -> Self {
+        self.reply_to_message_id = Some(message_id.into());
+        self
+    }
+
+    pub fn reply_parameters(mut self, parameters: ReplyParameters) -> Self {
+        self.reply_parameters = Some(parameters);
+        self
+    }
+
+    /// Accepts every markup type directly, e.g. an `InlineKeyboardMarkup`.
+    pub fn reply_markup<M: Into<ReplyMarkup>>(mut self, markup: M) -> Self {
+        self.reply_markup = Some(markup.into());
+        self
+    }
+
+    pub fn message_thread_id(mut self, thread_id: Integer) -> Self {
+        self.message_thread_id = Some(thread_id);
+        self
+    }
+
+    pub fn business_connection_id(mut self, id: String) -> Self {
+        self.business_connection_id = Some(id);
+        self
+    }
+
+    pub fn message_effect_id(mut self, effect_id: String) -> Self {
+        self.message_effect_id = Some(effect_id);
+        self
+    }
+
+    pub fn allow_sending_without_reply(mut self, allow: bool) -> Self {
+        self.allow_sending_without_reply = Some(allow);
+        self
+    }
+
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = Some(max_retries);
+        self
+    }
+}
+
+/// Optional parameters shared by the methods sending something else than a
+/// text message, e.g. `Api::send_photo`. All fields default to `None`,
+/// meaning that they are not sent.
+///
+/// These methods also accept an `Option<Integer>`, which is taken as
+/// `reply_to_message_id`:
+///
+/// ```no_run
+/// use telegram_bot::*;
+///
+/// let api = Api::from_env("TELEGRAM_BOT_TOKEN").unwrap();
+/// api.send_sticker(-1001234567, "sticker-id".to_string(), None, None, None).unwrap();
+/// api.send_sticker(-1001234567, "sticker-id".to_string(), None,
+///                  SendOptions::default().message_thread_id(42), None).unwrap();
+/// ```
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct SendOptions {
+    pub reply_to_message_id: Option<MessageId>,
+    /// Send even if the message given by `reply_to_message_id` doesn't exist
+    /// (anymore), without replying then. Setting it without
+    /// `reply_to_message_id` gives an `Error::InvalidArgument`.
+    pub allow_sending_without_reply: Option<bool>,
+    /// The forum topic to send to (supergroups with topics only)
+    pub message_thread_id: Option<Integer>,
+    /// Sends on behalf of the business account of this connection (see
+    /// `UpdateKind::BusinessConnection`). Not possible for invoices.
+    pub business_connection_id: Option<String>,
+}
+
+impl SendOptions {
+    pub fn reply_to_message_id<M: Into<MessageId>>(mut self, message_id: M) -> Self {
+        self.reply_to_message_id = Some(message_id.into());
+        self
+    }
+
+    pub fn allow_sending_without_reply(mut self, allow: bool) -> Self {
+        self.allow_sending_without_reply = Some(allow);
+        self
+    }
+
+    pub fn message_thread_id(mut self, thread_id: Integer) -> Self {
+        self.message_thread_id = Some(thread_id);
+        self
+    }
+
+    pub fn business_connection_id(mut self, id: String) -> Self {
+        self.business_connection_id = Some(id);
+        self
+    }
+}
+
+impl From<Option<MessageId>> for SendOptions {
+    fn from(reply_to_message_id: Option<MessageId>) -> SendOptions {
+        SendOptions {
+            reply_to_message_id: reply_to_message_id,
+            ..SendOptions::default()
+        }
+    }
+}
+
+/// Optional parameters of `Api::send_chat_action_with_options`. All fields
+/// default to `None`, meaning that they are not sent.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ChatActionOptions {
+    /// The forum topic the action is shown in (supergroups with topics only)
+    pub message_thread_id: Option<Integer>,
+    /// Shows the action on behalf of the business account of this
+    /// connection
+    pub business_connection_id: Option<String>,
+}
+
+impl ChatActionOptions {
+    pub fn message_thread_id(mut self, thread_id: Integer) -> Self {
+        self.message_thread_id = Some(thread_id);
+        self
+    }
+
+    pub fn business_connection_id(mut self, id: String) -> Self {
+        self.business_connection_id = Some(id);
+        self
+    }
+}
+
+// ---------------------------------------------------------------------------
+/// A file to send. Files that are already stored on the Telegram servers are
+/// referenced by their id, files on the web by their URL. Local files and
+/// files in memory are uploaded.
+///
+/// Strings are converted by checking if they name an existing local file.
+/// If they don't, they are treated as URL (if they start with `http://` or
+/// `https://`) or as file id.
+#[derive(Debug, PartialEq, Clone)]
+pub enum FileSource {
+    FileId(String),
+    Url(String),
+    File(PathBuf),
+    /// File contents that are uploaded directly from memory. `filename` is
+    /// shown to the users and `mime_type` is e.g. "image/png".
+    Bytes {
+        data: Vec<u8>,
+        filename: String,
+        mime_type: String,
+    },
+}
+
+impl From<String> for FileSource {
+    fn from(s: String) -> FileSource {
+        // When PathExt becomes stable, use Path::new(&path).exists() instead of this!
+        let is_file = fs::metadata(&s).map(|m| m.is_file()).unwrap_or(false);
+        if s.contains(".") && is_file {
+            FileSource::File(s.into())
+        } else if s.starts_with("http://") || s.starts_with("https://") {
+            FileSource::Url(s)
+        } else {
+            FileSource::FileId(s)
+        }
+    }
+}
+
+impl<'a> From<&'a str> for FileSource {
+    fn from(s: &'a str) -> FileSource {
+        FileSource::from(s.to_string())
+    }
+}
+
+impl From<PathBuf> for FileSource {
+    fn from(path: PathBuf) -> FileSource {
+        FileSource::File(path)
+    }
+}
+
+/// Creates a `FileSource::Bytes` from `(data, filename, mime_type)`.
+impl From<(Vec<u8>, String, String)> for FileSource {
+    fn from((data, filename, mime_type): (Vec<u8>, String, String)) -> FileSource {
+        FileSource::Bytes {
+            data: data,
+            filename: filename,
+            mime_type: mime_type,
+        }
+    }
+}
+
+impl<'a> From<&'a Path> for FileSource {
+    fn from(path: &'a Path) -> FileSource {
+        FileSource::File(path.to_path_buf())
+    }
+}
+
+// ---------------------------------------------------------------------------
+/// Represents the Telegram type "InputMedia": The new content of a message
+/// in `edit_message_media` or an item of `send_media_group`. Local files and
+/// files in memory are uploaded.
+#[derive(Debug, PartialEq, Clone)]
+pub enum InputMedia {
+    Photo(InputMediaPhoto),
+    Video(InputMediaVideo),
+    Document(InputMediaDocument),
+    Audio(InputMediaAudio),
+}
+
+impl From<InputMediaPhoto> for InputMedia {
+    fn from(photo: InputMediaPhoto) -> InputMedia {
+        InputMedia::Photo(photo)
+    }
+}
+
+impl From<InputMediaVideo> for InputMedia {
+    fn from(video: InputMediaVideo) -> InputMedia {
+        InputMedia::Video(video)
+    }
+}
+
+impl From<InputMediaDocument> for InputMedia {
+    fn from(document: InputMediaDocument) -> InputMedia {
+        InputMedia::Document(document)
+    }
+}
+
+impl From<InputMediaAudio> for InputMedia {
+    fn from(audio: InputMediaAudio) -> InputMedia {
+        InputMedia::Audio(audio)
+    }
+}
+
+/// Telegram type "InputMediaPhoto".
+#[derive(Debug, PartialEq, Clone)]
+pub struct InputMediaPhoto {
+    pub media: FileSource,
+    pub caption: Option<String>,
+    pub parse_mode: Option<ParseMode>,
+    /// Blur the photo until it's tapped
+    pub has_spoiler: bool,
+}
+
+impl InputMediaPhoto {
+    pub fn new<F: Into<FileSource>>(media: F) -> InputMediaPhoto {
+        InputMediaPhoto {
+            media: media.into(),
+            caption: None,
+            parse_mode: None,
+            has_spoiler: false,
+        }
+    }
+}
+
+/// Telegram type "InputMediaVideo".
+#[derive(Debug, PartialEq, Clone)]
+pub struct InputMediaVideo {
+    pub media: FileSource,
+    pub caption: Option<String>,
+    pub parse_mode: Option<ParseMode>,
+    pub width: Option<Integer>,
+    pub height: Option<Integer>,
+    pub duration: Option<Integer>,
+    pub supports_streaming: Option<bool>,
+    /// Blur the video until it's tapped
+    pub has_spoiler: bool,
+}
+
+impl InputMediaVideo {
+    pub fn new<F: Into<FileSource>>(media: F) -> InputMediaVideo {
+        InputMediaVideo {
+            media: media.into(),
+            caption: None,
+            parse_mode: None,
+            width: None,
+            height: None,
+            duration: None,
+            supports_streaming: None,
+            has_spoiler: false,
+        }
+    }
+}
+
+/// Telegram type "InputMediaDocument" (except "thumbnail").
+#[derive(Debug, PartialEq, Clone)]
+pub struct InputMediaDocument {
+    pub media: FileSource,
+    pub caption: Option<String>,
+    pub parse_mode: Option<ParseMode>,
+}
+
+impl InputMediaDocument {
+    pub fn new<F: Into<FileSource>>(media: F) -> InputMediaDocument {
+        InputMediaDocument {
+            media: media.into(),
+            caption: None,
+            parse_mode: None,
+        }
+    }
+}
+
+/// Telegram type "InputMediaAudio" (except "thumbnail").
+#[derive(Debug, PartialEq, Clone)]
+pub struct InputMediaAudio {
+    pub media: FileSource,
+    pub caption: Option<String>,
+    pub parse_mode: Option<ParseMode>,
+    pub duration: Option<Integer>,
+    pub performer: Option<String>,
+    pub title: Option<String>,
+}
+
+impl InputMediaAudio {
+    pub fn new<F: Into<FileSource>>(media: F) -> InputMediaAudio {
+        InputMediaAudio {
+            media: media.into(),
+            caption: None,
+            parse_mode: None,
+            duration: None,
+            performer: None,
+            title: None,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+/// Represents the Telegram type "BotCommandScope". Used to define for which
+/// users a list of bot commands is shown.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BotCommandScope {
+    Default,
+    AllPrivateChats,
+    AllGroupChats,
+    AllChatAdministrators,
+    /// Contains the chat id
+    Chat(ChatId),
+    /// Contains the chat id
+    ChatAdministrators(ChatId),
+    /// Contains the chat id and the user id
+    ChatMember(ChatId, UserId),
+}
+
+impl Encodable for BotCommandScope {
+    fn encode<E: Encoder>(&self, e: &mut E) -> Result<(), E::Error> {
+        let (typ, chat_id, user_id) = match *self {
+            BotCommandScope::Default => ("default", None, None),
+            BotCommandScope::AllPrivateChats => ("all_private_chats", None, None),
+            BotCommandScope::AllGroupChats => ("all_group_chats", None, None),
+            BotCommandScope::AllChatAdministrators => {
+                ("all_chat_administrators", None, None)
+            },
+            BotCommandScope::Chat(c) => ("chat", Some(c), None),
+            BotCommandScope::ChatAdministrators(c) => {
+                ("chat_administrators", Some(c), None)
+            },
+            BotCommandScope::ChatMember(c, u) => ("chat_member", Some(c), Some(u)),
+        };
+
+        e.emit_struct("BotCommandScope", 3, |e| {
+            try!(e.emit_struct_field("type", 0, |e| typ.encode(e)));
+            if let Some(c) = chat_id {
+                try!(e.emit_struct_field("chat_id", 1, |e| c.encode(e)));
+            }
+            if let Some(u) = user_id {
+                try!(e.emit_struct_field("user_id", 2, |e| u.encode(e)));
+            }
+            Ok(())
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+/// Represents the Telegram type "MenuButton": The button next to the input
+/// field of private chats with the bot (see `Api::set_chat_menu_button`).
+#[derive(Debug, PartialEq, Clone)]
+pub enum MenuButton {
+    /// Opens the list of bot commands
+    Commands,
+    /// Opens the web app at `url`
+    WebApp { text: String, url: String },
+    /// No specific button is set, so the default one is shown
+    Default,
+}
+
+impl Encodable for MenuButton {
+    fn encode<E: Encoder>(&self, e: &mut E) -> Result<(), E::Error> {
+        e.emit_struct("MenuButton", 3, |e| {
+            match *self {
+                MenuButton::Commands => {
+                    e.emit_struct_field("type", 0, |e| "commands".encode(e))
+                }
+                MenuButton::WebApp { ref text, ref url } => {
+                    try!(e.emit_struct_field("type", 0, |e| "web_app".encode(e)));
+                    try!(e.emit_struct_field("text", 1, |e| text.encode(e)));
+                    e.emit_struct_field("web_app", 2, |e| {
+                        WebAppInfo { url: url.clone() }.encode(e)
+                    })
+                }
+                MenuButton::Default => {
+                    e.emit_struct_field("type", 0, |e| "default".encode(e))
+                }
+            }
+        })
+    }
+}
+
+// Has to be implemented manually, because of the "type" field
+impl Decodable for MenuButton {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
+        d.read_struct("MenuButton", 0, |d| {
+            let typ: String = try_field!(d, "type");
+            match typ.as_str() {
+                "commands" => Ok(MenuButton::Commands),
+                "web_app" => {
+                    let web_app: WebAppInfo = try_field!(d, "web_app");
+                    Ok(MenuButton::WebApp {
+                        text: try_field!(d, "text"),
+                        url: web_app.url,
+                    })
+                }
+                "default" => Ok(MenuButton::Default),
+                _ => Err(d.error(&format!("Invalid menu button type: {}", typ))),
+            }
+        })
+    }
+}
+
 // ---------------------------------------------------------------------------
 /// Strongly typed ChatAction. Instead of passing a String to the
 /// `send_chat_action` method, this is used.
@@ -184,18 +879,18 @@ impl Encodable for ChatAction {
 #[derive(Debug, PartialEq, Clone)]
 pub enum Chat {
     Private {
-        id: Integer,
+        id: ChatId,
         first_name: String,
         last_name: Option<String>,
         username: Option<String>,
     },
     Group {
-        id: Integer,
+        id: ChatId,
         title: String,
         is_supergroup: bool
     },
     Channel {
-        id: Integer,
+        id: ChatId,
         title: String,
         name: Option<String>
     },
@@ -203,7 +898,7 @@ pub enum Chat {
 
 impl Chat {
     /// Returns the chat id, which is needed to send messages.
-    pub fn id(&self) -> Integer {
+    pub fn id(&self) -> ChatId {
         match self {
             &Chat::Private { id, .. } => id,
             &Chat::Group { id, .. } => id,
@@ -216,6 +911,12 @@ impl Chat {
         if let &Chat::Private {..} = self { true } else { false }
     }
 
+    /// Returns if the Chat is a private chat with a user. Same as `is_user`,
+    /// but named like the chat type "private" of the API.
+    pub fn is_private(&self) -> bool {
+        self.is_user()
+    }
+
     /// Returns if the Chat is a Group
     pub fn is_group(&self) -> bool {
         if let &Chat::Group { is_supergroup, .. } = self { !is_supergroup } else { false }
@@ -234,10 +935,14 @@ impl Chat {
     pub fn to_user(&self) -> Option<User> {
         if let &Chat::Private { id, ref first_name, ref last_name, ref username } = self {
             Some(User {
-                id: id,
+                id: UserId(id.0),
                 first_name: first_name.clone(),
                 last_name: last_name.clone(),
                 username: username.clone(),
+                is_bot: None,
+                can_join_groups: None,
+                can_read_all_group_messages: None,
+                supports_inline_queries: None,
             })
         } else { None }
     }
@@ -245,44 +950,48 @@ impl Chat {
 
 impl Decodable for Chat {
     fn decode<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
-        d.read_struct("Chat", 0, |d| {
-            // All kinds of chat have an 'id' and a 'type' fields
-            let id : Integer = try_field!(d, "id");
-            let typ: String = try_field!(d, "type");
+        d.read_struct("Chat", 0, decode_chat_fields)
+    }
+}
 
-            match typ.as_ref() {
-                "private" => {
-                    Ok(Chat::Private {
-                        id: id,
-                        first_name: try_field!(d, "first_name"),
-                        last_name: try_field!(d, "last_name"),
-                        username: try_field!(d, "username"),
-                    })
-                }
-                "group" => {
-                    Ok(Chat::Group {
-                        id: id,
-                        title: try_field!(d, "title"),
-                        is_supergroup: false
-                    })
-                }
-                "supergroup" => {
-                    Ok(Chat::Group {
-                        id: id,
-                        title: try_field!(d, "title"),
-                        is_supergroup: true
-                    })
-                }
-                "channel" => {
-                    Ok(Chat::Channel {
-                        id: id,
-                        title: try_field!(d, "title"),
-                        name: try_field!(d, "username"),
-                    })
-                }
-                _ => Err(d.error(&format!("Invalid chat type: {}", typ)))
-            }
-        })
+// Decodes the fields of a chat object. Used for `Chat` and `ChatFullInfo`,
+// which contain the same fields.
+fn decode_chat_fields<D: Decoder>(d: &mut D) -> Result<Chat, D::Error> {
+    // All kinds of chat have an 'id' and a 'type' fields
+    let id : ChatId = try_field!(d, "id");
+    let typ: String = try_field!(d, "type");
+
+    match typ.as_ref() {
+        "private" => {
+            Ok(Chat::Private {
+                id: id,
+                first_name: try_field!(d, "first_name"),
+                last_name: try_field!(d, "last_name"),
+                username: try_field!(d, "username"),
+            })
+        }
+        "group" => {
+            Ok(Chat::Group {
+                id: id,
+                title: try_field!(d, "title"),
+                is_supergroup: false
+            })
+        }
+        "supergroup" => {
+            Ok(Chat::Group {
+                id: id,
+                title: try_field!(d, "title"),
+                is_supergroup: true
+            })
+        }
+        "channel" => {
+            Ok(Chat::Channel {
+                id: id,
+                title: try_field!(d, "title"),
+                name: try_field!(d, "username"),
+            })
+        }
+        _ => Err(d.error(&format!("Invalid chat type: {}", typ)))
     }
 }
 
@@ -344,18 +1053,77 @@ impl Encodable for Chat {
 }
 
 // ---------------------------------------------------------------------------
+/// The result of `get_chat`: A `Chat` together with the additional fields
+/// only the full chat object has. Fields which are not set for the type of
+/// the chat are `None`.
 #[derive(Debug, PartialEq, Clone)]
-pub struct Message {
-    pub message_id: Integer,
-    pub from: User,
+pub struct ChatFullInfo {
     pub chat: Chat,
-    pub date: Integer,
+    pub photo: Option<ChatPhoto>,
+    pub description: Option<String>,
+    pub invite_link: Option<String>,
+    pub pinned_message: Option<Box<Message>>,
+    /// Default permissions of the members (groups and supergroups only)
+    pub permissions: Option<ChatPermissions>,
+    /// Name of the group's sticker set (supergroups only)
+    pub sticker_set_name: Option<String>,
+    /// Whether the bot may change the group's sticker set
+    pub can_set_sticker_set: Option<bool>,
+    /// The reactions allowed in the chat. `None` means that all emoji
+    /// reactions are allowed.
+    pub available_reactions: Option<Vec<ReactionType>>,
+    /// Identifier of the color used for the chat's name and replies
+    pub accent_color_id: Option<Integer>,
+    /// Custom emoji shown next to the name (private chats only)
+    pub emoji_status_custom_emoji_id: Option<String>,
+}
 
-    // forward_from and forward_date in one
-    pub forward: Option<(User, Integer)>,
+impl Decodable for ChatFullInfo {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
+        d.read_struct("ChatFullInfo", 0, |d| {
+            Ok(ChatFullInfo {
+                chat: try!(decode_chat_fields(d)),
+                photo: try_field!(d, "photo"),
+                description: try_field!(d, "description"),
+                invite_link: try_field!(d, "invite_link"),
+                pinned_message: try_field!(d, "pinned_message"),
+                permissions: try_field!(d, "permissions"),
+                sticker_set_name: try_field!(d, "sticker_set_name"),
+                can_set_sticker_set: try_field!(d, "can_set_sticker_set"),
+                available_reactions: try_field!(d, "available_reactions"),
+                accent_color_id: try_field!(d, "accent_color_id"),
+                emoji_status_custom_emoji_id: try_field!(d, "emoji_status_custom_emoji_id"),
+            })
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+#[derive(Debug, PartialEq, Clone)]
+pub struct Message {
+    pub message_id: MessageId,
+    /// The sender. Empty for messages posted to channels.
+    pub from: Option<User>,
+    pub chat: Chat,
+    pub date: Integer,
+    /// The forum topic the message belongs to (supergroups with topics only)
+    pub message_thread_id: Option<Integer>,
+
+    // forward_from and forward_date in one
+    pub forward: Option<(User, Integer)>,
+    /// The message this message replies to ("reply_to_message"). The
+    /// replied message itself never contains another reply.
     pub reply: Option<Box<Message>>,
+    /// The bot the message was sent through via an inline query
+    pub via_bot: Option<User>,
+    /// The business connection the message was received through. Has to be
+    /// given when replying on behalf of the business account.
+    pub business_connection_id: Option<String>,
 
     pub msg: MessageType,
+    /// Special entities like commands or links in the text. Empty for
+    /// messages that are no text messages.
+    pub entities: Vec<MessageEntity>,
 
     pub caption: Option<String>,
 }
@@ -371,31 +1139,222 @@ impl Decodable for Message {
                 (Some(from), Some(date)) => Some((from, date)),
                 _ => None,
             };
+            let maybe_entities: Option<Vec<MessageEntity>> = try_field!(d, "entities");
             Ok(Message {
                 message_id: try_field!(d, "message_id"),
                 from: try_field!(d, "from"),
                 chat: try_field!(d, "chat"),
                 date: try_field!(d, "date"),
+                message_thread_id: try_field!(d, "message_thread_id"),
                 forward: maybe_forward,
                 reply: try_field!(d, "reply_to_message"),
+                via_bot: try_field!(d, "via_bot"),
+                business_connection_id: try_field!(d, "business_connection_id"),
                 msg: try!(MessageType::decode(d)),
+                entities: maybe_entities.unwrap_or(Vec::new()),
                 caption: try_field!(d, "caption"),
             })
         })
     }
 }
 
+impl Message {
+    /// Creates a message with the given content and all optional fields
+    /// empty. Useful to construct messages in tests of handlers; the other
+    /// fields can be set afterwards:
+    ///
+    /// ```
+    /// use telegram_bot::*;
+    ///
+    /// let chat = Chat::Private {
+    ///     id: ChatId(123456789),
+    ///     first_name: "Test".into(),
+    ///     last_name: None,
+    ///     username: None,
+    /// };
+    /// let mut message = Message::new(1, chat, 1437821492,
+    ///                                MessageType::Text("/start".into()));
+    /// message.from = Some(User {
+    ///     id: UserId(123456789),
+    ///     first_name: "Test".into(),
+    ///     last_name: None,
+    ///     username: None,
+    ///     is_bot: Some(false),
+    ///     can_join_groups: None,
+    ///     can_read_all_group_messages: None,
+    ///     supports_inline_queries: None,
+    /// });
+    /// let update = Update::new(1, UpdateKind::Message(message));
+    /// ```
+    pub fn new<M: Into<MessageId>>(message_id: M, chat: Chat, date: Integer, msg: MessageType)
+                                   -> Message {
+        Message {
+            message_id: message_id.into(),
+            from: None,
+            chat: chat,
+            date: date,
+            message_thread_id: None,
+            forward: None,
+            reply: None,
+            via_bot: None,
+            business_connection_id: None,
+            msg: msg,
+            entities: Vec::new(),
+            caption: None,
+        }
+    }
+
+    /// Returns the id of the chat the message was sent to. Shorthand for
+    /// `message.chat.id()`.
+    pub fn chat_id(&self) -> ChatId {
+        self.chat.id()
+    }
+
+    /// Returns the id of the message, e.g. to reply to it.
+    pub fn message_id(&self) -> MessageId {
+        self.message_id
+    }
+
+    /// Returns the text, if the message is a text message.
+    pub fn text(&self) -> Option<&str> {
+        match self.msg {
+            MessageType::Text(ref text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Splits a text message starting with a command (like `/start` or
+    /// `/start@some_bot foo bar`) into the command without the leading `/`
+    /// and the bot name (`start`) and the rest of the text (`foo bar`).
+    /// Returns `None` if the message doesn't start with a command.
+    pub fn command(&self) -> Option<(&str, &str)> {
+        let text = match self.text() {
+            Some(text) if text.starts_with('/') => &text[1..],
+            _ => return None,
+        };
+
+        let (cmd, args) = match text.find(char::is_whitespace) {
+            Some(pos) => (&text[..pos], text[pos..].trim_start()),
+            None => (text, ""),
+        };
+        let cmd = cmd.split('@').next().unwrap_or("");
+
+        if cmd.is_empty() {
+            None
+        } else {
+            Some((cmd, args))
+        }
+    }
+
+    /// Returns true if the message starts with the command `name` (given
+    /// without the leading `/`), e.g. `/help` or `/help@some_bot` for
+    /// `"help"`.
+    pub fn is_command(&self, name: &str) -> bool {
+        self.command().map_or(false, |(cmd, _)| cmd == name)
+    }
+
+    /// Returns the largest size of the photo, if the message is a photo.
+    /// Handy to get the best version for downloading.
+    pub fn largest_photo(&self) -> Option<&PhotoSize> {
+        match self.msg {
+            MessageType::Photo(ref sizes) => {
+                sizes.iter().max_by_key(|p| p.width * p.height)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the id of the file the message contains, if it's a photo (the
+    /// largest size), audio, voice message, document, animation, sticker,
+    /// video or video note. Chat photos of service messages are not
+    /// included.
+    pub fn file_id(&self) -> Option<&str> {
+        let file_id = match self.msg {
+            MessageType::Photo(_) => return self.largest_photo().map(|p| &*p.file_id),
+            MessageType::Audio(ref a) => &a.file_id,
+            MessageType::Voice(ref v) => &v.file_id,
+            MessageType::File(ref d) => &d.file_id,
+            MessageType::Animation(ref a) => &a.file_id,
+            MessageType::Sticker(ref s) => &s.file_id,
+            MessageType::Video(ref v) => &v.file_id,
+            MessageType::VideoNote(ref v) => &v.file_id,
+            _ => return None,
+        };
+        Some(file_id)
+    }
+
+    /// Returns the users that joined the group, if this is a service message
+    /// about new members. Otherwise the slice is empty.
+    pub fn new_chat_members(&self) -> &[User] {
+        match self.msg {
+            MessageType::NewChatMembers(ref users) => users,
+            MessageType::NewChatParticipant(ref user) => ::std::slice::from_ref(user),
+            _ => &[],
+        }
+    }
+
+    /// Returns the user that left the group, if this is a service message
+    /// about a leaving member.
+    pub fn left_chat_member(&self) -> Option<&User> {
+        match self.msg {
+            MessageType::LeftChatMember(ref user) |
+            MessageType::LeftChatParticipant(ref user) => Some(user),
+            _ => None,
+        }
+    }
+
+    /// Returns the payment, if this is a service message about a successful
+    /// payment.
+    pub fn successful_payment(&self) -> Option<&SuccessfulPayment> {
+        match self.msg {
+            MessageType::SuccessfulPayment(ref payment) => Some(payment),
+            _ => None,
+        }
+    }
+
+    /// Returns true if this is a service message (e.g. about new members or
+    /// a new chat title) rather than content sent by a user.
+    pub fn is_service_message(&self) -> bool {
+        match self.msg {
+            MessageType::SuccessfulPayment(_) |
+            MessageType::WebAppData(_) |
+            MessageType::NewChatMembers(_) |
+            MessageType::LeftChatMember(_) |
+            MessageType::NewChatParticipant(_) |
+            MessageType::LeftChatParticipant(_) |
+            MessageType::NewChatTitle(_) |
+            MessageType::NewChatPhoto(_) |
+            MessageType::DeleteChatPhoto |
+            MessageType::GroupChatCreated |
+            MessageType::SuperGroupChatCreated(_) |
+            MessageType::ChannelChatCreated => true,
+            _ => false,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum MessageType {
     Text(String),
     Audio(Audio),
     Voice(Voice),
     File(Document),
+    Animation(Animation),
     Photo(Vec<PhotoSize>),
     Sticker(Sticker),
     Video(Video),
+    VideoNote(VideoNote),
     Contact(Contact),
     Location(Location),
+    Poll(Poll),
+    /// Service message about a payment to the bot
+    SuccessfulPayment(SuccessfulPayment),
+    /// Service message with data sent by a web app
+    WebAppData(WebAppData),
+    /// Service message about users that joined the group
+    NewChatMembers(Vec<User>),
+    /// Service message about a user that left the group
+    LeftChatMember(User),
     NewChatParticipant(User),
     LeftChatParticipant(User),
     NewChatTitle(String),
@@ -404,6 +1363,10 @@ pub enum MessageType {
     GroupChatCreated,
     SuperGroupChatCreated(GroupToSuperGroupMigration),
     ChannelChatCreated,
+    /// A kind of message this crate doesn't know yet, e.g. one added in a
+    /// newer version of the API. The other fields of the `Message` are still
+    /// decoded.
+    Unknown,
 }
 
 impl Decodable for MessageType {
@@ -427,12 +1390,23 @@ impl Decodable for MessageType {
         maybe_field!(d, "audio", Audio);
         maybe_field!(d, "voice", Voice);
         maybe_field!(d, "file", File);
+        // Animations also set the "document" field for backward
+        // compatibility, so they need to be checked first.
+        maybe_field!(d, "animation", Animation);
         maybe_field!(d, "photo", Photo);
         maybe_field!(d, "document", File);
         maybe_field!(d, "sticker", Sticker);
         maybe_field!(d, "video", Video);
+        maybe_field!(d, "video_note", VideoNote);
         maybe_field!(d, "contact", Contact);
         maybe_field!(d, "location", Location);
+        maybe_field!(d, "poll", Poll);
+        maybe_field!(d, "successful_payment", SuccessfulPayment);
+        maybe_field!(d, "web_app_data", WebAppData);
+        // The old "*_chat_participant" fields are still sent for backward
+        // compatibility, so the current ones need to be checked first.
+        maybe_field!(d, "new_chat_members", NewChatMembers);
+        maybe_field!(d, "left_chat_member", LeftChatMember);
         maybe_field!(d, "new_chat_participant", NewChatParticipant);
         maybe_field!(d, "left_chat_participant", LeftChatParticipant);
         maybe_field!(d, "new_chat_title", NewChatTitle);
@@ -461,8 +1435,10 @@ impl Decodable for MessageType {
             return Ok(MessageType::ChannelChatCreated);
         };
 
-        // None of the tested fields is present: This is an error
-        Err(d.error("No field for inferring message type is set"))
+        // None of the tested fields is present: The message type was added to
+        // the API after this crate was written. Telegram adds new types
+        // regularly, so this must not make decoding the message fail.
+        Ok(MessageType::Unknown)
     }
 }
 
@@ -479,7 +1455,10 @@ pub struct GroupToSuperGroupMigration {
 /// `send_message` method, this is used.
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum ParseMode {
+    /// Legacy Markdown, kept for backward compatibility. Prefer `MarkdownV2`.
     Markdown,
+    /// Markdown with additional entities like strikethrough and spoilers
+    MarkdownV2,
     Html,
 }
 
@@ -487,26 +1466,252 @@ impl fmt::Display for ParseMode {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", match *self {
             ParseMode::Markdown => "Markdown",
+            ParseMode::MarkdownV2 => "MarkdownV2",
             ParseMode::Html => "HTML",
         })
     }
 }
 
+impl ParseMode {
+    /// Escapes the characters that have a special meaning in this mode, so
+    /// that e.g. user supplied text can be put into a formatted message and
+    /// is shown as it is.
+    ///
+    /// ```
+    /// use telegram_bot::ParseMode;
+    ///
+    /// let name = "<b>_evil_</b>";
+    /// assert_eq!(ParseMode::Html.escape(name), "&lt;b&gt;_evil_&lt;/b&gt;");
+    /// assert_eq!(ParseMode::Markdown.escape(name), r"<b>\_evil\_</b>");
+    /// assert_eq!(ParseMode::MarkdownV2.escape("1+1=2!"), r"1\+1\=2\!");
+    /// ```
+    pub fn escape(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        for c in text.chars() {
+            match (*self, c) {
+                (ParseMode::Markdown, '_') | (ParseMode::Markdown, '*') |
+                (ParseMode::Markdown, '`') | (ParseMode::Markdown, '[') => {
+                    out.push('\\');
+                    out.push(c);
+                }
+                (ParseMode::MarkdownV2, c) if "_*[]()~`>#+-=|{}.!\\".contains(c) => {
+                    out.push('\\');
+                    out.push(c);
+                }
+                (ParseMode::Html, '<') => out.push_str("&lt;"),
+                (ParseMode::Html, '>') => out.push_str("&gt;"),
+                (ParseMode::Html, '&') => out.push_str("&amp;"),
+                (ParseMode::Html, '"') => out.push_str("&quot;"),
+                _ => out.push(c),
+            }
+        }
+        out
+    }
+}
+
+impl Encodable for ParseMode {
+    fn encode<E: Encoder>(&self, e: &mut E) -> Result<(), E::Error> {
+        e.emit_str(&self.to_string())
+    }
+}
+
+// ---------------------------------------------------------------------------
+/// Strongly typed poll type. Used in the `send_poll` method and the "type"
+/// field of "Poll".
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PollType {
+    Regular,
+    Quiz,
+}
+
+impl Decodable for PollType {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
+        Ok(match &*try!(d.read_str()) {
+            "regular" => PollType::Regular,
+            "quiz" => PollType::Quiz,
+            _ => return Err(d.error("Not a valid poll type")),
+        })
+    }
+}
+
+impl fmt::Display for PollType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match *self {
+            PollType::Regular => "regular",
+            PollType::Quiz => "quiz",
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+/// Strongly typed status of a chat member. Used in the "status" field of
+/// "ChatMember".
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ChatMemberStatus {
+    Creator,
+    Administrator,
+    Member,
+    Restricted,
+    Left,
+    Kicked,
+}
+
+impl Decodable for ChatMemberStatus {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
+        Ok(match &*try!(d.read_str()) {
+            "creator" => ChatMemberStatus::Creator,
+            "administrator" => ChatMemberStatus::Administrator,
+            "member" => ChatMemberStatus::Member,
+            "restricted" => ChatMemberStatus::Restricted,
+            "left" => ChatMemberStatus::Left,
+            "kicked" => ChatMemberStatus::Kicked,
+            _ => return Err(d.error("Not a valid chat member status")),
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+/// Telegram type "Poll". Needs to be decoded manually, because "type" is a
+/// keyword in Rust.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Poll {
+    pub id: String,
+    pub question: String,
+    pub options: Vec<PollOption>,
+    pub total_voter_count: Integer,
+    pub is_closed: bool,
+    pub is_anonymous: bool,
+    pub poll_type: PollType,
+    pub allows_multiple_answers: bool,
+    /// Only set for quizzes that were sent (or stopped) by the bot
+    pub correct_option_id: Option<Integer>,
+}
+
+impl Decodable for Poll {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
+        d.read_struct("Poll", 0, |d| {
+            Ok(Poll {
+                id: try_field!(d, "id"),
+                question: try_field!(d, "question"),
+                options: try_field!(d, "options"),
+                total_voter_count: try_field!(d, "total_voter_count"),
+                is_closed: try_field!(d, "is_closed"),
+                is_anonymous: try_field!(d, "is_anonymous"),
+                poll_type: try_field!(d, "type"),
+                allows_multiple_answers: try_field!(d, "allows_multiple_answers"),
+                correct_option_id: try_field!(d, "correct_option_id"),
+            })
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+/// Telegram type "MessageEntity". Needs to be decoded manually, because
+/// "type" is a keyword in Rust.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MessageEntity {
+    /// E.g. "mention", "bot_command", "url" or "bold"
+    pub entity_type: String,
+    /// Offset in UTF-16 code units. Use `extract` to get the entity's text.
+    pub offset: Integer,
+    /// Length in UTF-16 code units
+    pub length: Integer,
+    /// Only set for "text_link"
+    pub url: Option<String>,
+    /// Only set for "text_mention"
+    pub user: Option<User>,
+    /// Only set for "pre"
+    pub language: Option<String>,
+    /// Only set for "custom_emoji". See `Api::get_custom_emoji_stickers`.
+    pub custom_emoji_id: Option<String>,
+}
+
+impl MessageEntity {
+    /// Returns the part of `text` (the text of the message containing the
+    /// entity) covered by the entity. The API measures offset and length in
+    /// UTF-16 code units, so slicing the UTF-8 text directly would break for
+    /// e.g. emoji. Returns `None` if the entity doesn't fit the text.
+    pub fn extract(&self, text: &str) -> Option<String> {
+        let start = self.offset;
+        let end = self.offset + self.length;
+
+        // Find the byte indices of the first and the last UTF-16 position
+        let mut begin = None;
+        let mut pos = 0;
+        for (i, c) in text.char_indices() {
+            if pos == start {
+                begin = Some(i);
+            }
+            if pos == end {
+                return begin.map(|b| text[b..i].to_string());
+            }
+            pos += c.len_utf16() as Integer;
+        }
+        if pos == start {
+            begin = Some(text.len());
+        }
+        if pos == end {
+            begin.map(|b| text[b..].to_string())
+        } else {
+            None
+        }
+    }
+}
+
+impl Decodable for MessageEntity {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
+        d.read_struct("MessageEntity", 0, |d| {
+            Ok(MessageEntity {
+                entity_type: try_field!(d, "type"),
+                offset: try_field!(d, "offset"),
+                length: try_field!(d, "length"),
+                url: try_field!(d, "url"),
+                user: try_field!(d, "user"),
+                language: try_field!(d, "language"),
+                custom_emoji_id: try_field!(d, "custom_emoji_id"),
+            })
+        })
+    }
+}
+
 // ===========================================================================
 // Telegram types directly mapped to Rust types
 // ===========================================================================
 /// Telegram type "User" (directly mapped)
 #[derive(RustcDecodable, Debug, PartialEq, Clone)]
 pub struct User {
-    pub id: Integer,
+    pub id: UserId,
     pub first_name: String,
     pub last_name: Option<String>,
     pub username: Option<String>,
+    pub is_bot: Option<bool>,
+    /// Only returned by `Api::get_me`
+    pub can_join_groups: Option<bool>,
+    /// Only returned by `Api::get_me`. `false` if the privacy mode is
+    /// enabled, meaning that the bot only receives commands and replies in
+    /// groups.
+    pub can_read_all_group_messages: Option<bool>,
+    /// Only returned by `Api::get_me`
+    pub supports_inline_queries: Option<bool>,
 }
 
-impl_encode!(User, 4,
+impl_encode!(User, 8,
     [0 => id, 1 => first_name],
-    [2 => last_name, 3 => username]);
+    [2 => last_name, 3 => username, 4 => is_bot, 5 => can_join_groups,
+     6 => can_read_all_group_messages, 7 => supports_inline_queries]);
+
+// ---------------------------------------------------------------------------
+/// Telegram type "ChatPhoto" (directly mapped). Returned as part of
+/// `ChatFullInfo` by `Api::get_chat`. The file ids can only be used to
+/// download the photo (via `get_file` and `download_file_to`), not to send
+/// it.
+#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+pub struct ChatPhoto {
+    /// Small (160x160) version
+    pub small_file_id: String,
+    /// Big (640x640) version
+    pub big_file_id: String,
+}
 
 // ---------------------------------------------------------------------------
 /// Telegram type "PhotoSize" (directly mapped)
@@ -569,6 +1774,25 @@ impl_encode!(Document, 5,
     [0 => file_id, 1 => thumb],
     [2 => file_name, 3 => mime_type, 4 => file_size]);
 
+// ---------------------------------------------------------------------------
+/// Telegram type "Animation" (directly mapped). Animations are GIFs or H.264
+/// videos without sound.
+#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+pub struct Animation {
+    pub file_id: String,
+    pub width: Integer,
+    pub height: Integer,
+    pub duration: Integer,
+    pub thumb: Option<PhotoSize>,
+    pub file_name: Option<String>,
+    pub mime_type: Option<String>,
+    pub file_size: Option<Integer>,
+}
+
+impl_encode!(Animation, 8,
+    [0 => file_id, 1 => width, 2 => height, 3 => duration],
+    [4 => thumb, 5 => file_name, 6 => mime_type, 7 => file_size]);
+
 // ---------------------------------------------------------------------------
 /// Telegram type "Sticker" (directly mapped)
 #[derive(RustcDecodable, Debug, PartialEq, Clone)]
@@ -576,13 +1800,55 @@ pub struct Sticker {
     pub file_id: String,
     pub width: Integer,
     pub height: Integer,
+    /// True for .TGS animations
+    pub is_animated: bool,
+    /// True for .WEBM videos
+    pub is_video: bool,
     pub thumb: Option<PhotoSize>,
+    /// Emoji associated with the sticker
+    pub emoji: Option<String>,
+    /// Name of the sticker set the sticker belongs to
+    pub set_name: Option<String>,
     pub file_size: Option<Integer>,
 }
 
-impl_encode!(Sticker, 5,
-    [0 => file_id, 1 => width, 2 => height, 3 => thumb],
-    [4 => file_size]);
+impl_encode!(Sticker, 9,
+    [0 => file_id, 1 => width, 2 => height, 3 => is_animated, 4 => is_video,
+     5 => thumb],
+    [6 => emoji, 7 => set_name, 8 => file_size]);
+
+/// Format of a sticker file uploaded via `Api::upload_sticker_file`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum StickerFormat {
+    /// A .WEBP or .PNG image
+    Static,
+    /// A .TGS animation
+    Animated,
+    /// A .WEBM video
+    Video,
+}
+
+impl Into<&'static str> for StickerFormat {
+    fn into(self) -> &'static str {
+        match self {
+            StickerFormat::Static => "static",
+            StickerFormat::Animated => "animated",
+            StickerFormat::Video => "video",
+        }
+    }
+}
+
+impl ToString for StickerFormat {
+    fn to_string(&self) -> String {
+        Into::<&str>::into(*self).into()
+    }
+}
+
+impl Encodable for StickerFormat {
+    fn encode<E: Encoder>(&self, e: &mut E) -> Result<(), E::Error> {
+        e.emit_str((*self).into())
+    }
+}
 
 // ---------------------------------------------------------------------------
 /// Telegram type "Video" (directly mapped)
@@ -601,6 +1867,22 @@ impl_encode!(Video, 7,
     [0 => file_id, 1 => width, 2 => height, 3 => duration, 4 => thumb],
     [5 => mime_type, 6 => file_size]);
 
+// ---------------------------------------------------------------------------
+/// Telegram type "VideoNote" (directly mapped)
+#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+pub struct VideoNote {
+    pub file_id: String,
+    /// Width and height of the video
+    pub length: Integer,
+    pub duration: Integer,
+    pub thumb: Option<PhotoSize>,
+    pub file_size: Option<Integer>,
+}
+
+impl_encode!(VideoNote, 5,
+    [0 => file_id, 1 => length, 2 => duration],
+    [3 => thumb, 4 => file_size]);
+
 // ---------------------------------------------------------------------------
 /// Telegram type "Contact" (directly mapped)
 #[derive(RustcDecodable, Debug, PartialEq, Clone)]
@@ -608,12 +1890,14 @@ pub struct Contact {
     pub phone_number: String,
     pub first_name: String,
     pub last_name: Option<String>,
-    pub user_id: Option<Integer>,
+    pub user_id: Option<UserId>,
+    /// Additional data about the contact in the form of a vCard.
+    pub vcard: Option<String>,
 }
 
-impl_encode!(Contact, 4,
+impl_encode!(Contact, 5,
     [0 => phone_number, 1 => first_name],
-    [2 => last_name, 3 => user_id]);
+    [2 => last_name, 3 => user_id, 4 => vcard]);
 
 // ---------------------------------------------------------------------------
 /// Telegram type "Location" (directly mapped)
@@ -623,17 +1907,539 @@ pub struct Location {
     pub latitude: Float,
 }
 
+/// Optional parameters of `Api::send_location` and
+/// `Api::edit_message_live_location`. All fields default to `None`, meaning
+/// that they are not sent.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct LocationOptions {
+    /// Radius of uncertainty in meters, 0-1500
+    pub horizontal_accuracy: Option<Float>,
+    /// Direction the user is moving in degrees, 1-360. Live locations only.
+    pub heading: Option<Integer>,
+    /// Distance in meters, 1-100000, at which other users of the chat are
+    /// alerted when they come close. Live locations only.
+    pub proximity_alert_radius: Option<Integer>,
+}
+
+impl LocationOptions {
+    pub fn horizontal_accuracy(mut self, meters: Float) -> Self {
+        self.horizontal_accuracy = Some(meters);
+        self
+    }
+
+    pub fn heading(mut self, degrees: Integer) -> Self {
+        self.heading = Some(degrees);
+        self
+    }
+
+    pub fn proximity_alert_radius(mut self, meters: Integer) -> Self {
+        self.proximity_alert_radius = Some(meters);
+        self
+    }
+}
+
 // ---------------------------------------------------------------------------
-/// Telegram type "Update" (directly mapped)
-#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+/// Telegram type "PollOption" (directly mapped)
+#[derive(RustcDecodable, RustcEncodable, Debug, PartialEq, Clone)]
+pub struct PollOption {
+    pub text: String,
+    pub voter_count: Integer,
+}
+
+// ---------------------------------------------------------------------------
+/// Telegram type "Update". Of all the optional fields in the Telegram type
+/// only one is ever set, so the content of the update is stored in `kind`.
+#[derive(Debug, PartialEq, Clone)]
 pub struct Update {
     pub update_id: Integer,
-    pub message: Option<Message>
+    pub kind: UpdateKind,
+}
+
+impl Decodable for Update {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
+        d.read_struct("Update", 0, |d| {
+            Ok(Update {
+                update_id: try_field!(d, "update_id"),
+                kind: try!(UpdateKind::decode(d)),
+            })
+        })
+    }
+}
+
+impl Update {
+    /// Creates an update, e.g. to test handlers (see `Message::new`).
+    pub fn new(update_id: Integer, kind: UpdateKind) -> Update {
+        Update {
+            update_id: update_id,
+            kind: kind,
+        }
+    }
+
+    /// Decodes an update from its JSON object. Unlike decoding via
+    /// `json::decode`, updates of a kind this crate doesn't know yet are
+    /// returned as `UpdateKind::Unknown` instead of failing.
+    pub fn from_json(raw: Json) -> ::std::result::Result<Update, json::DecoderError> {
+        let unknown_id = match raw {
+            Json::Object(ref obj) if !UPDATE_KIND_FIELDS.iter().any(|f| obj.contains_key(*f)) => {
+                obj.get("update_id").and_then(Json::as_i64)
+            }
+            _ => None,
+        };
+        match unknown_id {
+            Some(id) => Ok(Update::new(id, UpdateKind::Unknown(raw))),
+            None => Update::decode(&mut json::Decoder::new(raw)),
+        }
+    }
+
+    /// Returns the content of the update. Same as the `kind` field, for
+    /// matching on a borrowed update.
+    pub fn kind(&self) -> &UpdateKind {
+        &self.kind
+    }
+
+    /// Returns the id of the chat the update belongs to, regardless of its
+    /// kind (e.g. the chat of the message of a callback query). Returns
+    /// `None` for kinds without a chat, like inline queries.
+    pub fn chat_id(&self) -> Option<ChatId> {
+        match self.kind {
+            UpdateKind::Message(ref m) |
+            UpdateKind::EditedMessage(ref m) |
+            UpdateKind::ChannelPost(ref m) |
+            UpdateKind::EditedChannelPost(ref m) |
+            UpdateKind::BusinessMessage(ref m) |
+            UpdateKind::EditedBusinessMessage(ref m) => Some(m.chat_id()),
+            UpdateKind::CallbackQuery(ref q) => q.message.as_ref().map(Message::chat_id),
+            UpdateKind::MyChatMember(ref u) |
+            UpdateKind::ChatMember(ref u) => Some(u.chat.id()),
+            UpdateKind::ChatJoinRequest(ref r) => Some(r.chat.id()),
+            UpdateKind::MessageReaction(ref r) => Some(r.chat.id()),
+            UpdateKind::BusinessConnection(ref c) => Some(c.user_chat_id),
+            UpdateKind::InlineQuery(_) |
+            UpdateKind::Poll(_) |
+            UpdateKind::PollAnswer(_) |
+            UpdateKind::ShippingQuery(_) |
+            UpdateKind::PreCheckoutQuery(_) |
+            UpdateKind::Unknown(_) => None,
+        }
+    }
+
+    /// Returns the user who triggered the update, regardless of its kind
+    /// (e.g. the sender of a message or the user who pressed the button of a
+    /// callback query). Returns `None` for kinds without a user, like channel
+    /// posts, stopped polls and anonymous reactions.
+    pub fn user(&self) -> Option<&User> {
+        match self.kind {
+            UpdateKind::Message(ref m) |
+            UpdateKind::EditedMessage(ref m) |
+            UpdateKind::ChannelPost(ref m) |
+            UpdateKind::EditedChannelPost(ref m) |
+            UpdateKind::BusinessMessage(ref m) |
+            UpdateKind::EditedBusinessMessage(ref m) => m.from.as_ref(),
+            UpdateKind::InlineQuery(ref q) => Some(&q.from),
+            UpdateKind::CallbackQuery(ref q) => Some(&q.from),
+            UpdateKind::PollAnswer(ref a) => Some(&a.user),
+            UpdateKind::MyChatMember(ref u) |
+            UpdateKind::ChatMember(ref u) => Some(&u.from),
+            UpdateKind::ChatJoinRequest(ref r) => Some(&r.from),
+            UpdateKind::ShippingQuery(ref q) => Some(&q.from),
+            UpdateKind::PreCheckoutQuery(ref q) => Some(&q.from),
+            UpdateKind::MessageReaction(ref r) => r.user.as_ref(),
+            UpdateKind::BusinessConnection(ref c) => Some(&c.user),
+            UpdateKind::Poll(_) |
+            UpdateKind::Unknown(_) => None,
+        }
+    }
+}
+
+/// The content of an `Update`. The variant is inferred from the field that
+/// is set in the JSON object.
+#[derive(Debug, PartialEq, Clone)]
+pub enum UpdateKind {
+    Message(Message),
+    EditedMessage(Message),
+    ChannelPost(Message),
+    EditedChannelPost(Message),
+    InlineQuery(InlineQuery),
+    CallbackQuery(CallbackQuery),
+    /// New state of a poll. Only sent for stopped polls and polls sent by
+    /// the bot.
+    Poll(Poll),
+    /// A user changed their answer in a non-anonymous poll sent by the bot.
+    /// Only sent if requested via `allowed_updates`.
+    PollAnswer(PollAnswer),
+    /// The status of the bot itself changed in a chat, e.g. because it was
+    /// added to a group or blocked by a user.
+    MyChatMember(ChatMemberUpdated),
+    /// The status of another member changed in a chat. Only sent if
+    /// requested via `allowed_updates` and if the bot is an administrator.
+    ChatMember(ChatMemberUpdated),
+    /// A user asked to join a chat in which the bot may approve members.
+    ChatJoinRequest(ChatJoinRequest),
+    /// Only sent for invoices with a flexible price. Has to be answered via
+    /// `answer_shipping_query`.
+    ShippingQuery(ShippingQuery),
+    /// Has to be answered via `answer_pre_checkout_query` before the payment
+    /// is executed.
+    PreCheckoutQuery(PreCheckoutQuery),
+    /// A user changed their reactions to a message. Only sent if requested
+    /// via `allowed_updates` and if the bot is an administrator.
+    MessageReaction(MessageReactionUpdated),
+    /// The bot was connected to or disconnected from a business account, or
+    /// the connection was changed.
+    BusinessConnection(BusinessConnection),
+    /// A new message in a chat of a connected business account
+    BusinessMessage(Message),
+    /// A message in a chat of a connected business account was edited
+    EditedBusinessMessage(Message),
+    /// An update of a kind this crate doesn't support yet. Contains the whole
+    /// update object. Only returned by `Update::from_json` (which is used by
+    /// `Listener` and `WebhookServer`).
+    Unknown(Json),
+}
+
+// Fields of the "Update" object the kind is inferred from. Has to match the
+// decoding of `UpdateKind`, which is checked in the tests.
+const UPDATE_KIND_FIELDS: &'static [&'static str] = &[
+    "message", "edited_message", "channel_post", "edited_channel_post", "inline_query",
+    "callback_query", "poll", "poll_answer", "my_chat_member", "chat_member",
+    "chat_join_request", "shipping_query", "pre_checkout_query", "message_reaction",
+    "business_connection", "business_message", "edited_business_message",
+];
+
+impl Decodable for UpdateKind {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
+        // Works like in `MessageType`: Return the variant for the first field
+        // that is present.
+        macro_rules! maybe_field {
+            ($d:ident, $name:expr, $variant:ident) => {{
+                if let Some(val) = try!($d.read_struct_field(
+                    $name, 0, Decodable::decode)) {
+                    return Ok(UpdateKind::$variant(val));
+                };
+            }}
+        }
+
+        maybe_field!(d, "message", Message);
+        maybe_field!(d, "edited_message", EditedMessage);
+        maybe_field!(d, "channel_post", ChannelPost);
+        maybe_field!(d, "edited_channel_post", EditedChannelPost);
+        maybe_field!(d, "inline_query", InlineQuery);
+        maybe_field!(d, "callback_query", CallbackQuery);
+        maybe_field!(d, "poll", Poll);
+        maybe_field!(d, "poll_answer", PollAnswer);
+        maybe_field!(d, "my_chat_member", MyChatMember);
+        maybe_field!(d, "chat_member", ChatMember);
+        maybe_field!(d, "chat_join_request", ChatJoinRequest);
+        maybe_field!(d, "shipping_query", ShippingQuery);
+        maybe_field!(d, "pre_checkout_query", PreCheckoutQuery);
+        maybe_field!(d, "message_reaction", MessageReaction);
+        maybe_field!(d, "business_connection", BusinessConnection);
+        maybe_field!(d, "business_message", BusinessMessage);
+        maybe_field!(d, "edited_business_message", EditedBusinessMessage);
+
+        Err(d.error("No field for inferring update kind is set"))
+    }
+}
+
+// ---------------------------------------------------------------------------
+/// Telegram type "InlineQuery" (directly mapped)
+#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+pub struct InlineQuery {
+    pub id: String,
+    pub from: User,
+    pub location: Option<Location>,
+    pub query: String,
+    pub offset: String,
+}
+
+// ---------------------------------------------------------------------------
+/// Telegram type "CallbackQuery" (directly mapped)
+#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+pub struct CallbackQuery {
+    pub id: String,
+    pub from: User,
+    pub message: Option<Message>,
+    pub inline_message_id: Option<String>,
+    pub chat_instance: String,
+    pub data: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+/// Represents the Telegram type "InlineQueryResult". Used to answer inline
+/// queries and web app queries (see `Api::answer_inline_query` and
+/// `Api::answer_web_app_query`).
+#[derive(Debug, PartialEq, Clone)]
+pub enum InlineQueryResult {
+    Article(InlineQueryResultArticle),
+    Photo(InlineQueryResultPhoto),
 }
 
-// impl_encode!(Update, 2,
-//     [0 => update_id],
-//     [1 => message]);
+impl From<InlineQueryResultArticle> for InlineQueryResult {
+    fn from(article: InlineQueryResultArticle) -> InlineQueryResult {
+        InlineQueryResult::Article(article)
+    }
+}
+
+impl From<InlineQueryResultPhoto> for InlineQueryResult {
+    fn from(photo: InlineQueryResultPhoto) -> InlineQueryResult {
+        InlineQueryResult::Photo(photo)
+    }
+}
+
+impl Encodable for InlineQueryResult {
+    fn encode<E: Encoder>(&self, e: &mut E) -> Result<(), E::Error> {
+        match *self {
+            InlineQueryResult::Article(ref a) => a.encode(e),
+            InlineQueryResult::Photo(ref p) => p.encode(e),
+        }
+    }
+}
+
+/// Telegram type "InlineQueryResultArticle": A text message to send.
+#[derive(Debug, PartialEq, Clone)]
+pub struct InlineQueryResultArticle {
+    pub id: String,
+    pub title: String,
+    pub input_message_content: InputTextMessageContent,
+    pub reply_markup: Option<InlineKeyboardMarkup>,
+    pub url: Option<String>,
+    pub description: Option<String>,
+}
+
+impl_encode!(InlineQueryResultArticle, 7, type = "article",
+    [1 => id, 2 => title, 3 => input_message_content],
+    [4 => reply_markup, 5 => url, 6 => description]);
+
+/// Telegram type "InlineQueryResultPhoto": A photo given by URL.
+#[derive(Debug, PartialEq, Clone)]
+pub struct InlineQueryResultPhoto {
+    pub id: String,
+    /// URL of the photo, which has to be a JPEG
+    pub photo_url: String,
+    pub thumbnail_url: String,
+    pub title: Option<String>,
+    pub caption: Option<String>,
+    pub reply_markup: Option<InlineKeyboardMarkup>,
+}
+
+impl_encode!(InlineQueryResultPhoto, 7, type = "photo",
+    [1 => id, 2 => photo_url, 3 => thumbnail_url],
+    [4 => title, 5 => caption, 6 => reply_markup]);
+
+/// Telegram type "InputTextMessageContent": The text of the message sent as
+/// result of an inline query.
+#[derive(Debug, PartialEq, Clone)]
+pub struct InputTextMessageContent {
+    pub message_text: String,
+    pub parse_mode: Option<ParseMode>,
+}
+
+impl_encode!(InputTextMessageContent, 2,
+    [0 => message_text],
+    [1 => parse_mode]);
+
+/// Telegram type "InlineQueryResultsButton": A button shown above the
+/// results of an inline query (see `Api::answer_inline_query`). Exactly one
+/// of the optional fields should be set, so better use the constructors.
+#[derive(Debug, PartialEq, Clone)]
+pub struct InlineQueryResultsButton {
+    pub text: String,
+    /// Web app opened when the button is pressed
+    pub web_app: Option<WebAppInfo>,
+    /// Parameter of the "/start" message sent to the bot when the button
+    /// is pressed, which opens the private chat with it
+    pub start_parameter: Option<String>,
+}
+
+impl InlineQueryResultsButton {
+    /// Creates a button opening the web app at `url`.
+    pub fn web_app(text: String, url: String) -> InlineQueryResultsButton {
+        InlineQueryResultsButton {
+            text: text,
+            web_app: Some(WebAppInfo { url: url }),
+            start_parameter: None,
+        }
+    }
+
+    /// Creates a button switching to the private chat with the bot, which
+    /// receives "/start `start_parameter`". The parameter may only contain
+    /// `A-Z`, `a-z`, `0-9`, `_` and `-` and has at most 64 characters.
+    pub fn start_parameter(text: String, start_parameter: String)
+                           -> InlineQueryResultsButton {
+        InlineQueryResultsButton {
+            text: text,
+            web_app: None,
+            start_parameter: Some(start_parameter),
+        }
+    }
+}
+
+impl_encode!(InlineQueryResultsButton, 3,
+    [0 => text],
+    [1 => web_app, 2 => start_parameter]);
+
+/// Telegram type "WebAppInfo" (directly mapped)
+#[derive(RustcDecodable, RustcEncodable, Debug, PartialEq, Clone)]
+pub struct WebAppInfo {
+    /// HTTPS URL of the web app
+    pub url: String,
+}
+
+/// Telegram type "SentWebAppMessage" (directly mapped). Returned by
+/// `Api::answer_web_app_query`.
+#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+pub struct SentWebAppMessage {
+    /// Only set if the message has an inline keyboard
+    pub inline_message_id: Option<String>,
+}
+
+/// Telegram type "WebAppData" (directly mapped): Data a web app sent to the
+/// bot (see `MessageType::WebAppData`).
+#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+pub struct WebAppData {
+    pub data: String,
+    /// Text of the keyboard button that opened the web app
+    pub button_text: String,
+}
+
+// ---------------------------------------------------------------------------
+/// Telegram type "PollAnswer" (directly mapped)
+#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+pub struct PollAnswer {
+    pub poll_id: String,
+    pub user: User,
+    /// The chosen options. Empty if the user retracted their vote.
+    pub option_ids: Vec<Integer>,
+}
+
+// ---------------------------------------------------------------------------
+/// Telegram type "ChatJoinRequest" (directly mapped, except "invite_link")
+#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+pub struct ChatJoinRequest {
+    pub chat: Chat,
+    pub from: User,
+    /// Id of the private chat with the user, which the bot may use to
+    /// contact them until the request is handled.
+    pub user_chat_id: Option<ChatId>,
+    pub date: Integer,
+    pub bio: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+/// Represents the Telegram type "ReactionType". Used by
+/// `Api::set_message_reaction` and in `MessageReactionUpdated`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ReactionType {
+    /// Contains the emoji, e.g. "👍"
+    Emoji(String),
+    /// Contains the id of the custom emoji
+    CustomEmoji(String),
+    /// A paid reaction. Can't be set by bots.
+    Paid,
+}
+
+impl Encodable for ReactionType {
+    fn encode<E: Encoder>(&self, e: &mut E) -> Result<(), E::Error> {
+        e.emit_struct("ReactionType", 2, |e| {
+            match *self {
+                ReactionType::Emoji(ref emoji) => {
+                    try!(e.emit_struct_field("type", 0, |e| "emoji".encode(e)));
+                    e.emit_struct_field("emoji", 1, |e| emoji.encode(e))
+                }
+                ReactionType::CustomEmoji(ref id) => {
+                    try!(e.emit_struct_field("type", 0, |e| "custom_emoji".encode(e)));
+                    e.emit_struct_field("custom_emoji_id", 1, |e| id.encode(e))
+                }
+                ReactionType::Paid => {
+                    e.emit_struct_field("type", 0, |e| "paid".encode(e))
+                }
+            }
+        })
+    }
+}
+
+// Has to be implemented manually, because of the "type" field
+impl Decodable for ReactionType {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
+        d.read_struct("ReactionType", 0, |d| {
+            let typ: String = try_field!(d, "type");
+            match typ.as_str() {
+                "emoji" => Ok(ReactionType::Emoji(try_field!(d, "emoji"))),
+                "custom_emoji" => {
+                    Ok(ReactionType::CustomEmoji(try_field!(d, "custom_emoji_id")))
+                }
+                "paid" => Ok(ReactionType::Paid),
+                _ => Err(d.error(&format!("Invalid reaction type: {}", typ))),
+            }
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+/// Telegram type "MessageReactionUpdated" (directly mapped)
+#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+pub struct MessageReactionUpdated {
+    pub chat: Chat,
+    pub message_id: MessageId,
+    /// Not set for anonymous reactions
+    pub user: Option<User>,
+    /// Only set for anonymous reactions, e.g. by anonymous administrators
+    pub actor_chat: Option<Chat>,
+    pub date: Integer,
+    pub old_reaction: Vec<ReactionType>,
+    pub new_reaction: Vec<ReactionType>,
+}
+
+// ---------------------------------------------------------------------------
+/// Telegram type "BusinessConnection" (directly mapped, except "rights")
+#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+pub struct BusinessConnection {
+    /// Has to be given to send messages on behalf of the business account
+    pub id: String,
+    /// The owner of the business account
+    pub user: User,
+    /// Id of the private chat with the owner
+    pub user_chat_id: ChatId,
+    pub date: Integer,
+    pub can_reply: Option<bool>,
+    /// False if the connection was removed
+    pub is_enabled: bool,
+}
+
+// ---------------------------------------------------------------------------
+/// Telegram type "ChatMember" (only the fields shared by all statuses)
+#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+pub struct ChatMember {
+    pub user: User,
+    pub status: ChatMemberStatus,
+    /// Only set for administrators and the creator
+    pub custom_title: Option<String>,
+    /// Only set for restricted and kicked members. 0 means forever.
+    pub until_date: Option<Integer>,
+}
+
+// ---------------------------------------------------------------------------
+/// Telegram type "ChatMemberUpdated" (directly mapped, except "invite_link")
+#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+pub struct ChatMemberUpdated {
+    pub chat: Chat,
+    /// The user who changed the status
+    pub from: User,
+    pub date: Integer,
+    pub old_chat_member: ChatMember,
+    pub new_chat_member: ChatMember,
+}
+
+// ---------------------------------------------------------------------------
+/// Telegram type "File" (directly mapped). Returned by `get_file`. Use
+/// `Api::file_url` to get the download URL.
+#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+pub struct File {
+    pub file_id: String,
+    pub file_size: Option<Integer>,
+    /// Not set if the file is too big to be downloaded by bots
+    pub file_path: Option<String>,
+}
 
 // ---------------------------------------------------------------------------
 /// Telegram type "UserProfilePhotos" (directly mapped)
@@ -645,28 +2451,394 @@ pub struct UserProfilePhotos {
 
 // ---------------------------------------------------------------------------
 /// Telegram type "ReplyKeyboardMarkup" (directly mapped)
-#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+#[derive(RustcDecodable, Debug, PartialEq, Clone, Default)]
 pub struct ReplyKeyboardMarkup {
     pub keyboard: Vec<Vec<String>>,
+    /// Keeps the keyboard shown instead of collapsing it to a button
+    pub is_persistent: Option<bool>,
     pub resize_keyboard: Option<bool>,
     pub one_time_keyboard: Option<bool>,
     pub selective: Option<bool>,
+    /// Shown in the input field while the keyboard is active, at most 64
+    /// characters
+    pub input_field_placeholder: Option<String>,
 }
 
-impl Default for ReplyKeyboardMarkup {
-    fn default() -> Self {
-        ReplyKeyboardMarkup {
-            keyboard: Vec::new(),
-            resize_keyboard: None,
-            one_time_keyboard: None,
-            selective: None,
+impl_encode!(ReplyKeyboardMarkup, 6,
+    [0 => keyboard],
+    [1 => is_persistent, 2 => resize_keyboard, 3 => one_time_keyboard,
+     4 => selective, 5 => input_field_placeholder]);
+
+// ---------------------------------------------------------------------------
+/// Telegram type "InlineKeyboardMarkup" (directly mapped)
+#[derive(RustcDecodable, Debug, PartialEq, Clone, Default)]
+pub struct InlineKeyboardMarkup {
+    pub inline_keyboard: Vec<Vec<InlineKeyboardButton>>,
+}
+
+impl_encode!(InlineKeyboardMarkup, 1,
+    [0 => inline_keyboard],
+    []);
+
+// ---------------------------------------------------------------------------
+/// Telegram type "InlineKeyboardButton" (directly mapped). Exactly one of the
+/// optional fields should be set.
+#[derive(RustcDecodable, Debug, PartialEq, Clone, Default)]
+pub struct InlineKeyboardButton {
+    pub text: String,
+    pub url: Option<String>,
+    pub callback_data: Option<String>,
+    pub switch_inline_query: Option<String>,
+    pub switch_inline_query_current_chat: Option<String>,
+}
+
+impl_encode!(InlineKeyboardButton, 5,
+    [0 => text],
+    [1 => url, 2 => callback_data, 3 => switch_inline_query,
+     4 => switch_inline_query_current_chat]);
+
+// ---------------------------------------------------------------------------
+/// Telegram type "ChatPermissions" (directly mapped). Fields that are `None`
+/// are not sent to Telegram.
+#[derive(RustcDecodable, Debug, PartialEq, Clone, Copy, Default)]
+pub struct ChatPermissions {
+    pub can_send_messages: Option<bool>,
+    pub can_send_media_messages: Option<bool>,
+    pub can_send_polls: Option<bool>,
+    pub can_send_other_messages: Option<bool>,
+    pub can_add_web_page_previews: Option<bool>,
+    pub can_change_info: Option<bool>,
+    pub can_invite_users: Option<bool>,
+    pub can_pin_messages: Option<bool>,
+}
+
+impl_encode!(ChatPermissions, 8,
+    [],
+    [0 => can_send_messages, 1 => can_send_media_messages,
+     2 => can_send_polls, 3 => can_send_other_messages,
+     4 => can_add_web_page_previews, 5 => can_change_info,
+     6 => can_invite_users, 7 => can_pin_messages]);
+
+// ---------------------------------------------------------------------------
+/// Telegram type "ForumTopic" (directly mapped). Returned by
+/// `Api::create_forum_topic`.
+#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+pub struct ForumTopic {
+    /// Identifies the topic, e.g. in `SendMessageOptions::message_thread_id`
+    pub message_thread_id: Integer,
+    pub name: String,
+    /// Color of the icon as RGB value
+    pub icon_color: Integer,
+    pub icon_custom_emoji_id: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+/// Telegram type "BotCommand" (directly mapped)
+#[derive(RustcDecodable, RustcEncodable, Debug, PartialEq, Clone)]
+pub struct BotCommand {
+    /// Text of the command without the leading slash
+    pub command: String,
+    pub description: String,
+}
+
+// ---------------------------------------------------------------------------
+/// Telegram type "LabeledPrice" (directly mapped). Used in `send_invoice`
+/// and `ShippingOption`.
+#[derive(RustcDecodable, RustcEncodable, Debug, PartialEq, Clone)]
+pub struct LabeledPrice {
+    pub label: String,
+    /// Price in the smallest units of the currency (e.g. cents)
+    pub amount: Integer,
+}
+
+impl LabeledPrice {
+    /// Creates a price from the given `Price`, which takes care of the
+    /// smallest units of the currency.
+    pub fn new(label: String, price: Price) -> LabeledPrice {
+        LabeledPrice {
+            label: label,
+            amount: price.amount(),
         }
     }
 }
 
-impl_encode!(ReplyKeyboardMarkup, 4,
-    [0 => keyboard],
-    [1 => resize_keyboard, 2 => one_time_keyboard, 3 => selective]);
+/// A currency given by its three-letter ISO 4217 code, together with the
+/// number of decimal places of its amounts. The API expects all amounts in
+/// the smallest unit of the currency, e.g. 100 for 1.00 USD but 100 for
+/// 100 JPY.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Currency {
+    code: [u8; 3],
+    exponent: u32,
+}
+
+impl Currency {
+    pub const EUR: Currency = Currency { code: *b"EUR", exponent: 2 };
+    pub const GBP: Currency = Currency { code: *b"GBP", exponent: 2 };
+    pub const JPY: Currency = Currency { code: *b"JPY", exponent: 0 };
+    pub const RUB: Currency = Currency { code: *b"RUB", exponent: 2 };
+    pub const USD: Currency = Currency { code: *b"USD", exponent: 2 };
+    /// Telegram Stars, which have no fractions
+    pub const XTR: Currency = Currency { code: *b"XTR", exponent: 0 };
+
+    /// Returns the currency with the given code. Amounts of the currencies
+    /// without decimal places (like JPY, KRW and VND) and with three decimal
+    /// places (like BHD, KWD and TND) are handled accordingly, all other
+    /// currencies are assumed to have two decimal places. For other
+    /// currencies, use `with_exponent`.
+    ///
+    /// Fails with `Error::InvalidArgument` if `code` isn't three uppercase
+    /// letters.
+    pub fn new(code: &str) -> ::Result<Currency> {
+        let exponent = match code {
+            "CLP" | "ISK" | "JPY" | "KRW" | "PYG" | "UGX" | "VND" | "XTR" => 0,
+            "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+            _ => 2,
+        };
+        Currency::with_exponent(code, exponent)
+    }
+
+    /// Returns the currency with the given code whose amounts have
+    /// `exponent` decimal places.
+    pub fn with_exponent(code: &str, exponent: u32) -> ::Result<Currency> {
+        let bytes = code.as_bytes();
+        if bytes.len() != 3 || !bytes.iter().all(|b| b.is_ascii_uppercase()) {
+            return Err(::Error::InvalidArgument(
+                format!("Invalid currency code {:?}", code)));
+        }
+        Ok(Currency {
+            code: [bytes[0], bytes[1], bytes[2]],
+            exponent: exponent,
+        })
+    }
+
+    /// Returns the three-letter code, e.g. "USD".
+    pub fn code(&self) -> &str {
+        // Only ASCII letters are ever stored
+        ::std::str::from_utf8(&self.code).unwrap_or("")
+    }
+
+    /// Returns the number of decimal places of amounts.
+    pub fn exponent(&self) -> u32 {
+        self.exponent
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// An amount of money in a `Currency`, stored in its smallest units:
+///
+/// ```
+/// use telegram_bot::*;
+///
+/// assert_eq!(Price::parse("1.99", Currency::USD).unwrap().amount(), 199);
+/// assert_eq!(Price::parse("500", Currency::JPY).unwrap().amount(), 500);
+/// assert!(Price::parse("1.999", Currency::USD).is_err());
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Price {
+    currency: Currency,
+    amount: Integer,
+}
+
+impl Price {
+    /// Creates a price of `amount` smallest units of the currency (e.g.
+    /// cents).
+    pub fn from_minor_units(amount: Integer, currency: Currency) -> Price {
+        Price {
+            currency: currency,
+            amount: amount,
+        }
+    }
+
+    /// Parses a decimal amount like "12.50" or "-3" (negative prices are
+    /// used for discounts). Amounts with more decimal places than the
+    /// currency has fail with `Error::InvalidArgument`, instead of being
+    /// rounded.
+    pub fn parse(amount: &str, currency: Currency) -> ::Result<Price> {
+        let invalid = || {
+            ::Error::InvalidArgument(format!("Invalid amount {:?} for {}", amount, currency))
+        };
+
+        let (negative, digits) = match amount.trim() {
+            s if s.starts_with('-') => (true, &s[1..]),
+            s => (false, s),
+        };
+        let (whole, fraction) = match digits.find('.') {
+            Some(pos) if pos + 1 < digits.len() => (&digits[..pos], &digits[pos + 1..]),
+            Some(_) => return Err(invalid()),
+            None => (digits, ""),
+        };
+        let is_number = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if whole.is_empty() || !is_number(whole) || !is_number(fraction)
+            || fraction.len() as u32 > currency.exponent {
+            return Err(invalid());
+        }
+
+        // Append zeros to the fraction, so that all digits form the amount
+        // in the smallest units
+        let mut units = String::from(whole);
+        units.push_str(fraction);
+        for _ in fraction.len() as u32..currency.exponent {
+            units.push('0');
+        }
+        let amount: Integer = try!(units.parse().map_err(|_| invalid()));
+        Ok(Price::from_minor_units(if negative { -amount } else { amount }, currency))
+    }
+
+    /// Returns the amount in the smallest units of the currency, like the
+    /// API expects it.
+    pub fn amount(&self) -> Integer {
+        self.amount
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+}
+
+// ---------------------------------------------------------------------------
+/// Telegram type "ShippingOption" (directly mapped). Used in
+/// `answer_shipping_query`.
+#[derive(RustcDecodable, RustcEncodable, Debug, PartialEq, Clone)]
+pub struct ShippingOption {
+    pub id: String,
+    pub title: String,
+    pub prices: Vec<LabeledPrice>,
+}
+
+// ---------------------------------------------------------------------------
+/// Answer to a `ShippingQuery` (see `answer_shipping_query`).
+#[derive(Debug, PartialEq, Clone)]
+pub enum ShippingAnswer {
+    /// Shipping to the address is possible with the given options
+    Options(Vec<ShippingOption>),
+    /// Shipping is not possible. The message is shown to the user.
+    Error(String),
+}
+
+// ---------------------------------------------------------------------------
+/// Telegram type "ShippingAddress" (directly mapped)
+#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+pub struct ShippingAddress {
+    /// ISO 3166-1 alpha-2 country code
+    pub country_code: String,
+    pub state: String,
+    pub city: String,
+    pub street_line1: String,
+    pub street_line2: String,
+    pub post_code: String,
+}
+
+// ---------------------------------------------------------------------------
+/// Telegram type "OrderInfo" (directly mapped)
+#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+pub struct OrderInfo {
+    pub name: Option<String>,
+    pub phone_number: Option<String>,
+    pub email: Option<String>,
+    pub shipping_address: Option<ShippingAddress>,
+}
+
+// ---------------------------------------------------------------------------
+/// Telegram type "ShippingQuery" (directly mapped)
+#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+pub struct ShippingQuery {
+    pub id: String,
+    pub from: User,
+    pub invoice_payload: String,
+    pub shipping_address: ShippingAddress,
+}
+
+// ---------------------------------------------------------------------------
+/// Telegram type "PreCheckoutQuery" (directly mapped)
+#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+pub struct PreCheckoutQuery {
+    pub id: String,
+    pub from: User,
+    pub currency: String,
+    /// Total price in the smallest units of the currency
+    pub total_amount: Integer,
+    pub invoice_payload: String,
+    pub shipping_option_id: Option<String>,
+    pub order_info: Option<OrderInfo>,
+}
+
+// ---------------------------------------------------------------------------
+/// Telegram type "SuccessfulPayment" (directly mapped)
+#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+pub struct SuccessfulPayment {
+    pub currency: String,
+    /// Total price in the smallest units of the currency
+    pub total_amount: Integer,
+    pub invoice_payload: String,
+    pub shipping_option_id: Option<String>,
+    pub order_info: Option<OrderInfo>,
+    pub telegram_payment_charge_id: String,
+    pub provider_payment_charge_id: String,
+}
+
+// ---------------------------------------------------------------------------
+/// Telegram type "StarAmount" (directly mapped): An amount of Telegram Stars.
+#[derive(RustcDecodable, Debug, PartialEq, Clone, Copy)]
+pub struct StarAmount {
+    /// Number of whole Stars
+    pub amount: Integer,
+    /// Additional fractions of Stars, in 1/10^9 Stars
+    pub nanostar_amount: Option<Integer>,
+}
+
+/// Telegram type "StarTransactions" (directly mapped). Returned by
+/// `Api::get_star_transactions`.
+#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+pub struct StarTransactions {
+    pub transactions: Vec<StarTransaction>,
+}
+
+/// Telegram type "StarTransaction" (directly mapped). Incoming transactions
+/// (e.g. payments by users) have a `source`, outgoing ones (e.g. refunds) a
+/// `receiver`.
+#[derive(RustcDecodable, Debug, PartialEq, Clone)]
+pub struct StarTransaction {
+    /// Unique id of the transaction. For payments by users, this is the
+    /// `telegram_payment_charge_id` of the `SuccessfulPayment`.
+    pub id: String,
+    /// Number of Stars transferred
+    pub amount: Integer,
+    pub nanostar_amount: Option<Integer>,
+    pub date: Integer,
+    pub source: Option<TransactionPartner>,
+    pub receiver: Option<TransactionPartner>,
+}
+
+/// Represents the Telegram type "TransactionPartner": The other side of a
+/// `StarTransaction`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TransactionPartner {
+    /// E.g. "user", "fragment" or "telegram_ads"
+    pub partner_type: String,
+    /// Only set for the type "user"
+    pub user: Option<User>,
+    /// Only set for the type "user": The payload of the paid invoice
+    pub invoice_payload: Option<String>,
+}
+
+// Has to be implemented manually, because of the "type" field
+impl Decodable for TransactionPartner {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
+        d.read_struct("TransactionPartner", 0, |d| {
+            Ok(TransactionPartner {
+                partner_type: try_field!(d, "type"),
+                user: try_field!(d, "user"),
+                invoice_payload: try_field!(d, "invoice_payload"),
+            })
+        })
+    }
+}
 
 // ===========================================================================
 // Unit tests (mainly encode & decode)
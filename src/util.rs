@@ -1,36 +1,259 @@
-use super::Result;
+use super::{Float, Integer, RateLimit, Result};
 use rustc_serialize::{json, Encodable};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
 
-// Type for managing GET and POST parameter
+// The bot token. It's wrapped, so that it's never accidentally printed: The
+// `Debug` output hides it and there is no `Display` implementation. Use
+// `secret` only where the token is really needed (i.e. to build URLs).
+#[derive(Clone)]
+pub struct Token(String);
+
+impl Token {
+    pub fn new(token: &str) -> Token {
+        Token(token.into())
+    }
+
+    pub fn secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Token(<hidden>)")
+    }
+}
+
+// Remembers the last `capacity` ids inserted. With a capacity of 0, no ids
+// are remembered at all.
+pub struct RecentIds {
+    capacity: usize,
+    order: VecDeque<Integer>,
+    ids: HashSet<Integer>,
+}
+
+impl RecentIds {
+    pub fn new(capacity: usize) -> RecentIds {
+        RecentIds {
+            capacity: capacity,
+            order: VecDeque::new(),
+            ids: HashSet::new(),
+        }
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.shrink();
+    }
+
+    pub fn contains(&self, id: Integer) -> bool {
+        self.ids.contains(&id)
+    }
+
+    // Returns false if the id is already known.
+    pub fn insert(&mut self, id: Integer) -> bool {
+        if self.capacity == 0 {
+            return true;
+        }
+        if !self.ids.insert(id) {
+            return false;
+        }
+        self.order.push_back(id);
+        self.shrink();
+        true
+    }
+
+    // Forgets the oldest ids until at most `capacity` are left
+    fn shrink(&mut self) {
+        while self.order.len() > self.capacity {
+            if let Some(id) = self.order.pop_front() {
+                self.ids.remove(&id);
+            }
+        }
+    }
+}
+
+// Token buckets per chat. Every bucket holds up to `limit.messages` tokens
+// and is refilled at the rate given by the limit. Sending a message takes one
+// token. Tokens may be taken in advance, so concurrent senders queue up
+// instead of all waking up at the same time. Chats are identified by the
+// chat id parameter as it's sent, so usernames like "@channel" work too.
+pub struct RateLimiter {
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+// Every bucket keeps the limit it's filled with, since the limits of private
+// chats and groups differ.
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    capacity: f64,
+    per_sec: f64,
+}
+
+impl Bucket {
+    fn tokens_at(&self, now: Instant) -> f64 {
+        let refill = seconds(now.duration_since(self.updated)) * self.per_sec;
+        (self.tokens + refill).min(self.capacity)
+    }
+}
+
+// Buckets are only cleaned up once there are more than this many
+const MAX_IDLE_BUCKETS: usize = 1000;
+
+impl RateLimiter {
+    pub fn new() -> RateLimiter {
+        RateLimiter {
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Blocks until a message may be sent to the given chat.
+    pub fn acquire(&self, chat_id: &str, limit: &RateLimit) {
+        if let Some(wait) = self.reserve(chat_id, limit, Instant::now()) {
+            thread::sleep(wait);
+        }
+    }
+
+    // Takes a token of the chat at `now` and returns how long to wait until
+    // the message may be sent.
+    pub fn reserve(&self, chat_id: &str, limit: &RateLimit, now: Instant) -> Option<Duration> {
+        let capacity = limit.messages as f64;
+        let per_sec = capacity / seconds(limit.per);
+        if capacity <= 0.0 || !per_sec.is_finite() {
+            return None;
+        }
+
+        let mut buckets = match self.buckets.lock() {
+            Ok(buckets) => buckets,
+            Err(_) => return None,
+        };
+
+        if buckets.len() > MAX_IDLE_BUCKETS {
+            // Full buckets are the same as no bucket
+            buckets.retain(|_, b| b.tokens_at(now) < b.capacity);
+        }
+
+        let bucket = buckets.entry(chat_id.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+            capacity: capacity,
+            per_sec: per_sec,
+        });
+        // The limit may have been changed since the bucket was created
+        let tokens = bucket.tokens_at(now);
+        bucket.capacity = capacity;
+        bucket.per_sec = per_sec;
+        bucket.tokens = tokens.min(capacity) - 1.0;
+        bucket.updated = now;
+
+        if bucket.tokens < 0.0 {
+            Some(Duration::from_millis((-bucket.tokens / per_sec * 1000.0).ceil() as u64))
+        } else {
+            None
+        }
+    }
+}
+
+fn seconds(d: Duration) -> f64 {
+    d.as_secs() as f64 + d.subsec_nanos() as f64 / 1e9
+}
+
+/// The parameters of a request to an API method, in the order they were
+/// added. Used for `Api::api_call`, e.g. in extension traits that add
+/// methods this library doesn't offer (yet):
+///
+/// ```no_run
+/// use telegram_bot::*;
+///
+/// trait ChatMemberCount {
+///     fn get_chat_member_count(&self, chat_id: Integer) -> Result<Integer>;
+/// }
+///
+/// impl ChatMemberCount for Api {
+///     fn get_chat_member_count(&self, chat_id: Integer) -> Result<Integer> {
+///         let mut params = Params::new();
+///         params.add_get("chat_id", chat_id);
+///         self.api_call("getChatMemberCount", params)
+///     }
+/// }
+/// ```
+///
+/// Values are converted to strings. Arrays and objects are encoded as JSON
+/// via `add_get_json`, like the API expects.
+#[derive(Clone, Debug)]
 pub struct Params<'a> {
     gets: Vec<(&'a str, String)>,
 }
 
 impl<'a> Params<'a> {
+    /// Creates an empty parameter list.
     pub fn new() -> Params<'a> {
         Params {
             gets: Vec::new(),
         }
     }
 
+    /// Adds the parameter if `value` is `Some`; optional parameters that
+    /// are `None` are not sent at all.
     pub fn add_get_opt<T: ToString>(&mut self, key: &'a str, value: Option<T>) {
         if let Some(d) = value {
             self.gets.push((key, d.to_string()));
         }
     }
+
+    /// Adds a parameter with a string, number or boolean value.
     pub fn add_get<T: ToString>(&mut self, key: &'a str, value: T) {
         self.gets.push((key, value.to_string()));
     }
 
+    /// Adds a parameter with formatted text, which is formatted right into
+    /// the parameter list.
+    pub fn add_get_fmt(&mut self, key: &'a str, value: fmt::Arguments) {
+        self.gets.push((key, fmt::format(value)));
+    }
+
+    /// Adds a parameter with a floating point value, e.g. a latitude.
+    pub fn add_get_float(&mut self, key: &'a str, value: Float) {
+        self.add_get(key, value);
+    }
+
+    /// Encodes the value as JSON. Used for all array and object parameters.
+    pub fn add_get_json<T: Encodable>(&mut self,
+                        key: &'a str, value: T) -> Result<()> {
+        self.gets.push((key, try!(json::encode(&value))));
+        Ok(())
+    }
+
+    /// Like `add_get_json`, but only adds the parameter if `value` is
+    /// `Some`.
     pub fn add_get_json_opt<T: Encodable>(&mut self,
                             key: &'a str, value: Option<T>) -> Result<()> {
-        if let Some(d) = value {
-            self.gets.push((key, try!(json::encode(&d))));
+        match value {
+            Some(d) => self.add_get_json(key, d),
+            None => Ok(()),
         }
-        Ok(())
     }
 
-    pub fn get_params(&self) -> &Vec<(&str, String)> {
+    // Returns all parameters in the order they were added.
+    pub(crate) fn get_params(&self) -> &[(&'a str, String)] {
         &self.gets
     }
+
+    /// Returns the value of the parameter `key`, if it was added.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.gets.iter().find(|&&(k, _)| k == key).map(|&(_, ref v)| &**v)
+    }
+
+    // Replaces the value of the parameter, or adds it if it doesn't exist.
+    pub(crate) fn set<T: ToString>(&mut self, key: &'a str, value: T) {
+        match self.gets.iter_mut().find(|&&mut (k, _)| k == key) {
+            Some(&mut (_, ref mut v)) => *v = value.to_string(),
+            None => self.gets.push((key, value.to_string())),
+        }
+    }
 }
@@ -0,0 +1,182 @@
+//! Receiving updates via webhook.
+
+use std::io::{self, Read, Write};
+use std::net::ToSocketAddrs;
+use rustc_serialize::json::Json;
+use hyper::Server;
+use hyper::header::Headers;
+use hyper::method::Method;
+use hyper::server::{Handler, Listening, Request, Response};
+use hyper::status::StatusCode;
+use hyper::uri::RequestUri;
+
+use super::{Api, Result, Update};
+use util::Token;
+
+// Header in which Telegram sends the secret token given to `set_webhook`
+const SECRET_TOKEN_HEADER: &'static str = "X-Telegram-Bot-Api-Secret-Token";
+
+/// A HTTP server receiving updates the Telegram servers push to the webhook
+/// (see `Api::set_webhook`).
+///
+/// Every webhook URL has to contain a secret path segment, so that nobody
+/// except Telegram (who knows the URL) can send fake updates. Requests with
+/// any other path are rejected. Register the webhook with an URL like
+/// `https://example.com/<secret>` and let your reverse proxy (which has to
+/// handle TLS) forward the requests to this server.
+///
+/// Additionally, a secret token can be configured via `set_secret_token`
+/// (it has to match the one given to `Api::set_webhook`). Requests without
+/// the correct token in the "X-Telegram-Bot-Api-Secret-Token" header are
+/// answered with `403 Forbidden`.
+///
+/// ```no_run
+/// use telegram_bot::*;
+///
+/// let api = Api::from_env("TELEGRAM_BOT_TOKEN").unwrap();
+/// let server = WebhookServer::new(api, "some-long-secret");
+/// let _listening = server.listen("127.0.0.1:8443", |api, u| {
+///     if let UpdateKind::Message(m) = u.kind {
+///         try!(api.send_message(m.chat.id(), "Hi!".into(),
+///                               None, None, None, None));
+///     }
+///     Ok(())
+/// }).unwrap();
+/// // The server runs until `_listening` is dropped.
+/// ```
+pub struct WebhookServer {
+    api: Api,
+    secret: String,
+    secret_token: Option<Token>,
+}
+
+impl WebhookServer {
+    /// Creates a server that answers updates via `api` and only accepts
+    /// requests whose path contains `secret` as a segment.
+    pub fn new(api: Api, secret: &str) -> WebhookServer {
+        WebhookServer {
+            api: api,
+            secret: secret.into(),
+            secret_token: None,
+        }
+    }
+
+    /// Sets the secret token every request has to contain in its
+    /// "X-Telegram-Bot-Api-Secret-Token" header. Defaults to `None`, meaning
+    /// that the header is not checked.
+    pub fn set_secret_token(&mut self, token: Option<&str>) {
+        self.secret_token = token.map(Token::new);
+    }
+
+    /// Binds the server to `addr` and starts handling updates in background
+    /// threads. For every update, `handler` is called with the `Api` (to
+    /// reply to the update) and the update itself.
+    ///
+    /// Every valid request is answered with `200 OK` before the handler is
+    /// called, so even slow handlers don't make Telegram send the update
+    /// again. Errors returned by the handler are printed to stderr.
+    ///
+    /// The server stops when the returned `Listening` is closed. Dropping it
+    /// blocks until the server has stopped.
+    pub fn listen<A, H>(self, addr: A, handler: H) -> Result<Listening>
+        where A: ToSocketAddrs,
+              H: Fn(&Api, Update) -> Result<()> + Sync + Send + 'static
+    {
+        let server = try!(Server::http(addr));
+        let listening = try!(server.handle(WebhookHandler {
+            api: self.api,
+            secret: self.secret,
+            secret_token: self.secret_token,
+            handler: handler,
+        }));
+        Ok(listening)
+    }
+}
+
+// Decodes the body of a webhook request, keeping updates of unknown kinds.
+fn decode_update(body: &str) -> ::std::result::Result<Update, String> {
+    let raw = try!(Json::from_str(body).map_err(|e| e.to_string()));
+    Update::from_json(raw).map_err(|e| e.to_string())
+}
+
+// Compares two secrets. The time it takes only depends on the lengths, so
+// that it doesn't reveal how many bytes of a guessed secret were correct.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+// Whether the path of `uri` contains `secret` as a segment. All segments are
+// compared, even after a match.
+pub(crate) fn has_secret(uri: &RequestUri, secret: &str) -> bool {
+    match *uri {
+        RequestUri::AbsolutePath(ref path) => {
+            // Ignore the query string
+            let path = path.split('?').next().unwrap_or("");
+            !secret.is_empty() && path.split('/').fold(false, |found, s| {
+                constant_time_eq(s.as_bytes(), secret.as_bytes()) | found
+            })
+        }
+        _ => false,
+    }
+}
+
+// Whether `headers` contain the secret token, if one is configured.
+pub(crate) fn has_secret_token(headers: &Headers, secret_token: Option<&Token>) -> bool {
+    match secret_token {
+        None => true,
+        Some(token) => match headers.get_raw(SECRET_TOKEN_HEADER) {
+            Some(values) => values.len() == 1
+                && constant_time_eq(&values[0], token.secret().as_bytes()),
+            None => false,
+        },
+    }
+}
+
+struct WebhookHandler<H> {
+    api: Api,
+    secret: String,
+    secret_token: Option<Token>,
+    handler: H,
+}
+
+impl<H> Handler for WebhookHandler<H>
+    where H: Fn(&Api, Update) -> Result<()> + Sync + Send
+{
+    fn handle(&self, mut req: Request, mut res: Response) {
+        // Check the request and decode the update
+        let update = if req.method != Method::Post {
+            Err(StatusCode::MethodNotAllowed)
+        } else if !has_secret(&req.uri, &self.secret) {
+            Err(StatusCode::NotFound)
+        } else if !has_secret_token(&req.headers, self.secret_token.as_ref()) {
+            Err(StatusCode::Forbidden)
+        } else {
+            let mut body = String::new();
+            match req.read_to_string(&mut body) {
+                Ok(_) => decode_update(&body).map_err(|e| {
+                    let _ = writeln!(io::stderr(),
+                                     "telegram-bot: webhook update could not be decoded: {}",
+                                     e);
+                    StatusCode::BadRequest
+                }),
+                Err(_) => Err(StatusCode::BadRequest),
+            }
+        };
+
+        // Answer the request before handling the update
+        *res.status_mut() = match update {
+            Ok(_) => StatusCode::Ok,
+            Err(status) => status,
+        };
+        if res.send(b"").is_err() {
+            return;
+        }
+
+        if let Ok(update) = update {
+            if let Err(e) = (self.handler)(&self.api, update) {
+                let _ = writeln!(io::stderr(),
+                                 "telegram-bot: webhook handler failed: {}", e);
+            }
+        }
+    }
+}